@@ -1,4 +1,4 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use std::{
     fs::File,
     io::{BufRead, BufReader},
@@ -14,50 +14,104 @@ fn main() {
 fn run(path: &str) -> Result<(String, String)> {
     let now = Instant::now();
     let file = File::open(path)?;
-    let numbers: Vec<i32> = BufReader::new(file)
-        .lines()
-        .map(|s| parse_number(s?.as_str()))
-        .collect::<Result<Vec<_>>>()?;
+    let numbers = parse(BufReader::new(file))?;
     println!("duration parsing : {:?}", now.elapsed());
 
+    Ok(solve(&numbers))
+}
+
+fn solve(numbers: &[i32]) -> (String, String) {
     let now = Instant::now();
-    let part1 = part1(&numbers);
+    let part1 = part1(numbers);
     println!("duration part 1 : {:?}", now.elapsed());
 
     let now = Instant::now();
-    let part2 = part2(&numbers);
+    let part2 = part2(numbers);
     println!("duration part 2 : {:?}", now.elapsed());
 
-    Ok((part1.to_string(), part2.to_string()))
+    (part1.to_string(), part2.to_string())
 }
 
 fn part1(numbers: &[i32]) -> usize {
+    zero_crossing_steps(numbers).len()
+}
+
+fn zero_crossing_steps(numbers: &[i32]) -> Vec<usize> {
     numbers
         .iter()
         .scan(50, |state, val| {
             *state = (*state + *val).rem_euclid(100);
             Some(*state)
         })
-        .filter(|pos| *pos == 0)
-        .count()
+        .enumerate()
+        .filter(|(_, pos)| *pos == 0)
+        .map(|(step, _)| step)
+        .collect()
 }
 
 fn part2(numbers: &[i32]) -> i32 {
-    numbers
-        .iter()
-        .scan(50, |state, val| {
-            let (new_pos, by_zero) = get_by_zero(*state, *val);
-            *state = new_pos;
-            Some(by_zero)
-        })
-        .sum()
+    match find_period(numbers) {
+        Some(period) => part2_periodic(numbers, period),
+        None => count_by_zero(50, numbers).1,
+    }
+}
+
+fn count_by_zero(start: i32, numbers: &[i32]) -> (i32, i32) {
+    numbers.iter().fold((start, 0), |(pos, count), val| {
+        let (new_pos, by_zero) = get_by_zero(pos, *val);
+        (new_pos, count + by_zero)
+    })
+}
+
+fn find_period(numbers: &[i32]) -> Option<usize> {
+    (1..=numbers.len() / 2).find(|period| {
+        numbers[*period..]
+            .iter()
+            .zip(numbers)
+            .all(|(val, prev)| val == prev)
+    })
+}
+
+fn part2_periodic(numbers: &[i32], period: usize) -> i32 {
+    //a period only depends on its start position, so there are at most 100 to compute
+    let mut by_start: [Option<(i32, i32)>; 100] = [None; 100];
+    let full_periods = numbers.len() / period;
+    let mut pos = 50;
+    let mut total = 0;
+    for _ in 0..full_periods {
+        let (new_pos, by_zero) =
+            *by_start[pos as usize].get_or_insert_with(|| count_by_zero(pos, &numbers[..period]));
+        pos = new_pos;
+        total += by_zero;
+    }
+    total + count_by_zero(pos, &numbers[full_periods * period..]).1
+}
+
+#[allow(dead_code)]
+fn all_positions_visited(start: i32, steps: &[i32]) -> Vec<i32> {
+    let mut pos = start;
+    let mut visited = vec![];
+    for step in steps {
+        for _ in 0..step.abs() {
+            pos = (pos + step.signum()).rem_euclid(100);
+            visited.push(pos);
+        }
+    }
+    visited
 }
 
 fn get_by_zero(pos: i32, turn: i32) -> (i32, i32) {
-    let quot = (pos + turn).div_euclid(100);
-    let new_pos = (pos + turn) - quot * 100;
+    get_by_zero_mod(pos, turn, 100)
+}
+
+fn get_by_zero_mod(pos: i32, turn: i32, modulus: i32) -> (i32, i32) {
+    let quot = (pos + turn).div_euclid(modulus);
+    let new_pos = (pos + turn).rem_euclid(modulus);
 
-    let by_zero = if new_pos == 0 && quot <= 0 {
+    //starting on zero going left does not count the start
+    let by_zero = if pos == 0 && new_pos == 0 && quot <= 0 {
+        -quot
+    } else if new_pos == 0 && quot <= 0 {
         -quot + 1
     } else if pos == 0 && quot < 0 {
         -(quot + 1)
@@ -68,6 +122,17 @@ fn get_by_zero(pos: i32, turn: i32) -> (i32, i32) {
     (new_pos, by_zero)
 }
 
+fn parse<R: BufRead>(reader: R) -> Result<Vec<i32>> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(line_no, line)| {
+            let line = line?;
+            parse_number(&line).with_context(|| format!("line {}: {}", line_no + 1, line))
+        })
+        .collect()
+}
+
 fn parse_number(line: &str) -> Result<i32> {
     if let Some(negative) = line.strip_prefix("L") {
         Ok(negative.parse::<i32>().map(|nb| -nb)?)
@@ -83,28 +148,42 @@ mod tests {
     use super::*;
     use proptest::prelude::*;
 
+    const TEST_NUMBERS: [i32; 10] = [-68, -30, 48, -5, 60, -55, -1, -99, 14, -82];
+
     #[test]
     fn test_part() {
-        let (part1, part2) = run("./files/test.txt").expect("could not run");
+        let (part1, part2) = solve(&TEST_NUMBERS);
         assert_eq!(&part1, "3");
         assert_eq!(&part2, "6");
     }
 
+    #[test]
+    fn test_run_matches_solve() {
+        let result = run("./files/test.txt").expect("could not run");
+        assert_eq!(result, solve(&TEST_NUMBERS));
+    }
+
     #[test]
     fn test_get_by_zero_exact() {
         let (new_pos, by_zero) = get_by_zero(50, 150);
 
         assert_eq!(by_zero, 2);
-        assert_eq!(new_pos, 0)
+        assert_eq!(new_pos, 0);
+
+        assert_eq!(get_by_zero_mod(50, 75, 100), get_by_zero(50, 75));
+        assert_eq!(get_by_zero_mod(3, -7, 5), (1, 1));
+        assert_eq!(get_by_zero(0, -200), (0, 2));
+        assert_eq!(get_by_zero_mod(0, -648, 4), (0, 162));
     }
 
     proptest! {
         #[test]
-        fn test_get_by_zero(pos in 0..99i32, step in -1000..1000i32) {
+        fn test_get_by_zero(pos in 0..199i32, step in -1000..1000i32, modulus in 2..200i32) {
             if step == 0 {
                 return Ok(())
             }
-            let (new_pos, by_zero) = get_by_zero(pos, step);
+            let pos = pos % modulus;
+            let (new_pos, by_zero) = get_by_zero_mod(pos, step, modulus);
 
             let min_step = if step < 0 {
                 -1
@@ -115,7 +194,7 @@ mod tests {
             let exp_by_zero = (1..step.abs()+1)
                 .map(|x| pos + x * min_step)
                 .filter(|int_pos| {
-                    let ret = int_pos.rem_euclid(100) == 0;
+                    let ret = int_pos.rem_euclid(modulus) == 0;
                     if ret {
                         dbg!(int_pos);
                     }
@@ -124,7 +203,69 @@ mod tests {
                 .count();
 
             assert_eq!(by_zero, exp_by_zero as i32, "should have crossed zero the same number of times");
-            assert_eq!((pos + step - new_pos).rem_euclid(100), 0, "should have landed on the same pos")
+            assert_eq!((pos + step - new_pos).rem_euclid(modulus), 0, "should have landed on the same pos")
         }
     }
+
+    #[test]
+    fn test_zero_crossing_steps() {
+        let numbers = TEST_NUMBERS;
+        let steps = zero_crossing_steps(&numbers);
+        assert_eq!(steps, vec![2, 5, 7]);
+        assert_eq!(steps.len(), part1(&numbers));
+
+        let positions: Vec<i32> = numbers
+            .iter()
+            .scan(50, |state, val| {
+                *state = (*state + *val).rem_euclid(100);
+                Some(*state)
+            })
+            .collect();
+        assert!(steps.iter().all(|step| positions[*step] == 0));
+    }
+
+    #[test]
+    fn test_parse_error_line() {
+        let numbers = parse(&b"L68\nR48\n"[..]).expect("could not parse");
+        assert_eq!(numbers, vec![-68, 48]);
+
+        let err = parse(&b"L68\nX12\nR5\n"[..]).unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("line 2:"), "{message}");
+        assert!(
+            message.contains("did not start with L or R : X12"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_all_positions_visited() {
+        assert_eq!(
+            all_positions_visited(50, &[3, -2]),
+            vec![51, 52, 53, 52, 51]
+        );
+        assert_eq!(all_positions_visited(1, &[-2]), vec![0, 99]);
+
+        let visited = all_positions_visited(50, &TEST_NUMBERS);
+        let zeros = visited.iter().filter(|pos| **pos == 0).count();
+        assert_eq!(zeros as i32, part2(&TEST_NUMBERS));
+    }
+
+    #[test]
+    fn test_find_period() {
+        assert_eq!(find_period(&TEST_NUMBERS), None);
+        assert_eq!(find_period(&[1, 2, 1, 3]), None);
+        assert_eq!(find_period(&[5, 5, 5]), Some(1));
+        assert_eq!(find_period(&[-68, 30, -68, 30, -68]), Some(2));
+    }
+
+    #[test]
+    fn test_part2_periodic() {
+        let numbers: Vec<i32> = TEST_NUMBERS.iter().cycle().take(1003).copied().collect();
+        assert_eq!(find_period(&numbers), Some(TEST_NUMBERS.len()));
+        assert_eq!(part2(&numbers), count_by_zero(50, &numbers).1);
+
+        let numbers: Vec<i32> = [37, -250, 18].iter().cycle().take(302).copied().collect();
+        assert_eq!(part2_periodic(&numbers, 3), count_by_zero(50, &numbers).1);
+    }
 }
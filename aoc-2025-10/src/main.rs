@@ -1,13 +1,18 @@
+use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use good_lp::Constraint;
 use good_lp::Expression;
 use good_lp::ProblemVariables;
 use good_lp::Solution;
+use good_lp::SolutionStatus;
 use good_lp::SolverModel;
 use good_lp::scip;
 use good_lp::variable;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::ops::{BitAnd, BitOr, BitXor};
 use std::{
     fs::File,
     io::{BufRead, BufReader},
@@ -25,6 +30,60 @@ impl Indicators {
             list: BTreeSet::from_iter(list),
         }
     }
+
+    fn contains(&self, pos: usize) -> bool {
+        self.list.contains(&pos)
+    }
+
+    fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[allow(dead_code)]
+    fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+impl BitAnd for &Indicators {
+    type Output = Indicators;
+
+    fn bitand(self, other: &Indicators) -> Indicators {
+        Indicators {
+            list: self.list.intersection(&other.list).copied().collect(),
+        }
+    }
+}
+
+impl BitOr for &Indicators {
+    type Output = Indicators;
+
+    fn bitor(self, other: &Indicators) -> Indicators {
+        Indicators {
+            list: self.list.union(&other.list).copied().collect(),
+        }
+    }
+}
+
+impl BitXor for &Indicators {
+    type Output = Indicators;
+
+    fn bitxor(self, other: &Indicators) -> Indicators {
+        Indicators {
+            list: self
+                .list
+                .symmetric_difference(&other.list)
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for Indicators {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let positions: Vec<String> = self.list.iter().map(|pos| pos.to_string()).collect();
+        write!(f, "{{{}}}", positions.join(", "))
+    }
 }
 
 struct Machine {
@@ -34,7 +93,138 @@ struct Machine {
 }
 
 impl Machine {
+    #[allow(dead_code)]
+    fn from_matrix(
+        target: Vec<bool>,
+        matrix: Vec<Vec<bool>>,
+        joltage: Vec<usize>,
+    ) -> Result<Machine> {
+        if joltage.len() != target.len() {
+            return Err(anyhow!(
+                "joltage has {} values but target has {}",
+                joltage.len(),
+                target.len()
+            ));
+        }
+        if let Some(row) = matrix.iter().position(|row| row.len() != target.len()) {
+            return Err(anyhow!(
+                "button {row} has {} indicators but target has {}",
+                matrix[row].len(),
+                target.len()
+            ));
+        }
+
+        let to_indicators = |row: Vec<bool>| {
+            Indicators::new(
+                row.into_iter()
+                    .enumerate()
+                    .filter(|(_, on)| *on)
+                    .map(|(pos, _)| pos)
+                    .collect(),
+            )
+        };
+        Ok(Machine {
+            target: to_indicators(target),
+            buttons: matrix.into_iter().map(to_indicators).collect(),
+            joltage,
+        })
+    }
+
+    //row echelon basis of the buttons over GF(2), keyed by the pivot of each row
+    fn button_basis(&self) -> BTreeMap<usize, BTreeSet<usize>> {
+        let mut basis = BTreeMap::new();
+        for button in self.buttons.iter() {
+            let row = reduce_gf2(&basis, button.list.clone());
+            if let Some(pivot) = row.first() {
+                basis.insert(*pivot, row);
+            }
+        }
+        basis
+    }
+
+    fn is_feasible(&self) -> bool {
+        reduce_gf2(&self.button_basis(), self.target.list.clone()).is_empty()
+    }
+
+    //None when there are more targets than fit in a usize
+    #[allow(dead_code)]
+    fn count_possible_targets(&self) -> Option<usize> {
+        1usize.checked_shl(self.button_basis().len() as u32)
+    }
+
+    fn press_lower_bound(&self) -> usize {
+        let mut sizes: Vec<usize> = self
+            .buttons
+            .iter()
+            .map(|button| button.list.len())
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut covered = 0;
+        sizes
+            .into_iter()
+            .take_while(|size| {
+                let missing = covered < self.target.list.len();
+                covered += size;
+                missing
+            })
+            .count()
+    }
+
+    //greedy count is only returned when it reaches the lower bound, so it is optimal
+    fn try_greedy(&self) -> Option<usize> {
+        let mut state = Indicators::new(vec![]);
+        let mut pressed = vec![false; self.buttons.len()];
+        let mut count = 0;
+
+        for _ in 0..self.buttons.len() * 2 {
+            if state == self.target {
+                break;
+            }
+            let (index, next_state) = self
+                .buttons
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !pressed[*index])
+                .map(|(index, button)| (index, &state ^ button))
+                .min_by_key(|(_, next_state)| (next_state ^ &self.target).len())?;
+            pressed[index] = true;
+            state = next_state;
+            count += 1;
+        }
+
+        (state == self.target && count == self.press_lower_bound()).then_some(count)
+    }
+
     fn find_shortest_button_press(&self) -> Result<f64> {
+        if !self.is_feasible() {
+            return Err(anyhow!("machine is infeasible"));
+        }
+        if let Some(count) = self.try_greedy() {
+            return Ok(count as f64);
+        }
+        self.solve_with_selection().map(|(presses, _)| presses)
+    }
+
+    #[allow(dead_code)]
+    fn find_shortest_button_press_with_timeout(&self, timeout_secs: f64) -> Result<Option<f64>> {
+        if !self.is_feasible() {
+            return Err(anyhow!("machine is infeasible"));
+        }
+        if let Some(count) = self.try_greedy() {
+            return Ok(Some(count as f64));
+        }
+        Ok(self
+            .solve_parity(Some(timeout_secs))?
+            .map(|(presses, _)| presses))
+    }
+
+    fn solve_with_selection(&self) -> Result<(f64, Vec<usize>)> {
+        self.solve_parity(None)?
+            .ok_or(anyhow!("solver stopped before finding an optimal solution"))
+    }
+
+    fn solve_parity(&self, timeout_secs: Option<f64>) -> Result<Option<(f64, Vec<usize>)>> {
         let mut problem = ProblemVariables::new();
         let but_vars = problem.add_vector(variable().integer().min(0).max(1), self.buttons.len());
         let eveness_vars = problem.add_vector(variable().integer().min(0), self.joltage.len());
@@ -60,7 +250,7 @@ impl Machine {
             .zip(eveness_vars.iter())
             .enumerate()
             .map(|(pos, (constraint, e_var))| {
-                if self.target.list.contains(&pos) {
+                if self.target.contains(pos) {
                     Expression::eq(constraint, *e_var * 2 + 1)
                 } else {
                     Expression::eq(constraint, *e_var * 2)
@@ -68,13 +258,25 @@ impl Machine {
             })
             .collect();
 
+        let mut model = problem.minimise(&obj).using(scip).with_all(constraints);
+        if let Some(timeout_secs) = timeout_secs {
+            model = model.set_option("limits/time", timeout_secs);
+        }
+        let solution = model.solve()?;
+        if !matches!(solution.status(), SolutionStatus::Optimal) {
+            return Ok(None);
+        }
+
+        //read which buttons were pressed
+        let selection = but_vars
+            .iter()
+            .enumerate()
+            .filter(|(_, var)| solution.value(**var) > 0.5)
+            .map(|(index, _)| index)
+            .collect();
+
         //evaluate sum of button presses
-        Ok(problem
-            .minimise(&obj)
-            .using(scip)
-            .with_all(constraints)
-            .solve()?
-            .eval(obj))
+        Ok(Some((solution.eval(obj), selection)))
     }
 
     fn find_shortest_button_press_joltage(&self) -> Result<f64> {
@@ -113,6 +315,16 @@ impl Machine {
     }
 }
 
+fn reduce_gf2(
+    basis: &BTreeMap<usize, BTreeSet<usize>>,
+    mut row: BTreeSet<usize>,
+) -> BTreeSet<usize> {
+    while let Some(basis_row) = row.first().and_then(|pivot| basis.get(pivot)) {
+        row = row.symmetric_difference(basis_row).copied().collect();
+    }
+    row
+}
+
 fn main() {
     let (part1, part2) = run("./files/input.txt").expect("could not run");
     println!("part1 : {part1}");
@@ -157,14 +369,42 @@ fn part2(machines: &[Machine]) -> usize {
         .sum()
 }
 
+//each machine builds its own SCIP instance, which SCIP supports across threads
+#[allow(dead_code)]
+fn part1_parallel(machines: &[Machine]) -> usize {
+    machines
+        .par_iter()
+        .map(|machine| {
+            machine
+                .find_shortest_button_press()
+                .expect("could not solve machine") as usize
+        })
+        .sum()
+}
+
+#[allow(dead_code)]
+fn part2_parallel(machines: &[Machine]) -> usize {
+    machines
+        .par_iter()
+        .map(|machine| {
+            machine
+                .find_shortest_button_press_joltage()
+                .expect("could not solve machine") as usize
+        })
+        .sum()
+}
+
 fn parse_file(path: &str) -> Result<Vec<Machine>> {
     let file = File::open(path)?;
     let machines: Vec<Machine> = BufReader::new(file)
         .lines()
-        .map(|res_line| {
+        .enumerate()
+        .map(|(index, res_line)| {
             res_line
-                .map_err(|_| anyhow!("could not read line"))
-                .and_then(|line| parse_line(&line))
+                .map_err(|_| anyhow!("could not read line {}", index + 1))
+                .and_then(|line| {
+                    parse_line(&line).with_context(|| format!("line {}: '{line}'", index + 1))
+                })
         })
         .collect::<Result<Vec<_>>>()?;
     Ok(machines)
@@ -176,16 +416,17 @@ fn parse_line(line: &str) -> Result<Machine> {
     let mut joltage: Option<Vec<usize>> = None;
 
     for word in line.split_whitespace() {
+        let context = || format!("while parsing token '{word}' in line '{line}'");
         if word.starts_with('[') {
-            target = Some(parse_target(&word[1..word.len() - 1])?)
+            target = Some(parse_target(&word[1..word.len() - 1]).with_context(context)?)
         } else if word.starts_with('(') {
-            buttons.push(parse_button(&word[1..word.len() - 1])?)
+            buttons.push(parse_button(&word[1..word.len() - 1]).with_context(context)?)
         } else if word.starts_with('{') {
-            joltage = Some(parse_voltage(&word[1..word.len() - 1])?)
+            joltage = Some(parse_voltage(&word[1..word.len() - 1]).with_context(context)?)
         }
     }
-    let target = target.ok_or(anyhow!("did not find target"))?;
-    let joltage = joltage.ok_or(anyhow!("did not find joltage"))?;
+    let target = target.ok_or_else(|| anyhow!("did not find target in line '{line}'"))?;
+    let joltage = joltage.ok_or_else(|| anyhow!("did not find joltage in line '{line}'"))?;
     Ok(Machine {
         target,
         buttons,
@@ -234,4 +475,199 @@ mod tests {
         assert_eq!(&part1, "7");
         assert_eq!(&part2, "33");
     }
+
+    #[test]
+    fn test_try_greedy() {
+        let machine = parse_line("[###.] (0,1,2) (0) (1) (3) {1,1,1,1}").expect("could not parse");
+        assert_eq!(machine.try_greedy(), Some(1));
+        assert_eq!(
+            machine
+                .find_shortest_button_press()
+                .expect("could not solve"),
+            1.0
+        );
+
+        let machines = parse_file("./files/test.txt").expect("could not parse");
+        for machine in machines.iter() {
+            let optimal = machine
+                .find_shortest_button_press()
+                .expect("could not solve");
+            if let Some(count) = machine.try_greedy() {
+                assert_eq!(count as f64, optimal);
+            }
+        }
+        //greedy reaches the first target in 3 presses but 2 are enough
+        assert_eq!(machines[0].try_greedy(), None);
+    }
+
+    #[test]
+    fn test_is_feasible() {
+        let machines = parse_file("./files/test.txt").expect("could not parse");
+        assert!(machines.iter().all(|machine| machine.is_feasible()));
+
+        let machine = parse_line("[.#..] (0,2) (2) (0,2,3) {1,1,1,1}").expect("could not parse");
+        assert!(!machine.is_feasible());
+        assert!(machine.find_shortest_button_press().is_err());
+    }
+
+    #[test]
+    fn test_solve_with_selection() {
+        let machines = parse_file("./files/test.txt").expect("could not parse");
+        for machine in machines.iter() {
+            let (presses, selection) = machine.solve_with_selection().expect("could not solve");
+            assert_eq!(presses, selection.len() as f64);
+
+            let state = selection
+                .iter()
+                .fold(Indicators::new(vec![]), |state, index| {
+                    &state ^ &machine.buttons[*index]
+                });
+            assert_eq!(state, machine.target);
+        }
+    }
+
+    #[test]
+    fn test_parallel() {
+        let machines = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(part1_parallel(&machines), part1(&machines));
+        assert_eq!(part2_parallel(&machines), part2(&machines));
+    }
+
+    #[test]
+    fn test_indicators() {
+        let indicators1 = Indicators::new(vec![0, 3, 7]);
+        let indicators2 = Indicators::new(vec![3, 5]);
+        assert_eq!(&indicators1 & &indicators2, Indicators::new(vec![3]));
+        assert_eq!(
+            &indicators1 | &indicators2,
+            Indicators::new(vec![0, 3, 5, 7])
+        );
+        assert_eq!(&indicators1 ^ &indicators2, Indicators::new(vec![0, 5, 7]));
+        assert!(indicators1.contains(7));
+        assert!(!indicators1.contains(5));
+        assert_eq!(indicators1.len(), 3);
+        assert!(!indicators1.is_empty());
+        assert!(Indicators::new(vec![]).is_empty());
+        assert_eq!(indicators1.to_string(), "{0, 3, 7}");
+        assert_eq!(Indicators::new(vec![]).to_string(), "{}");
+    }
+
+    #[test]
+    fn test_count_possible_targets() {
+        let machine = parse_line("[...] (0) (1,2) (0,2) (1) {1,1,1}").expect("could not parse");
+        assert_eq!(machine.count_possible_targets(), Some(8));
+
+        let machines = parse_file("./files/test.txt").expect("could not parse");
+        let counts: Vec<Option<usize>> = machines
+            .iter()
+            .map(|machine| machine.count_possible_targets())
+            .collect();
+        assert_eq!(counts, vec![Some(16), Some(16), Some(8)]);
+
+        //one independent button per light
+        let independent = |lights: usize| {
+            let buttons: Vec<String> = (0..lights).map(|light| format!("({light})")).collect();
+            let line = format!(
+                "[{}] {} {{{}}}",
+                ".".repeat(lights),
+                buttons.join(" "),
+                vec!["1"; lights].join(",")
+            );
+            parse_line(&line).expect("could not parse")
+        };
+        assert_eq!(independent(63).count_possible_targets(), Some(1 << 63));
+        assert_eq!(independent(64).count_possible_targets(), None);
+        assert_eq!(independent(70).count_possible_targets(), None);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let machines = parse_file("./files/test.txt").expect("could not parse");
+        for machine in machines.iter() {
+            assert_eq!(
+                machine
+                    .find_shortest_button_press_with_timeout(60.0)
+                    .expect("could not solve"),
+                Some(
+                    machine
+                        .find_shortest_button_press()
+                        .expect("could not solve")
+                )
+            );
+        }
+
+        let buttons: Vec<String> = (0..20)
+            .map(|i| format!("({i})"))
+            .chain(
+                (0..40).map(|i| format!("({},{},{})", i % 20, (i * 7 + 3) % 20, (i * 13 + 5) % 20)),
+            )
+            .collect();
+        let line = format!(
+            "[#.#.#..##.#.##..#.##] {} {{{}}}",
+            buttons.join(" "),
+            ["1"; 20].join(",")
+        );
+        let machine = parse_line(&line).expect("could not parse");
+        assert_eq!(
+            machine
+                .find_shortest_button_press_with_timeout(0.0)
+                .expect("could not solve"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_line("[.##.] (3) (1,x) {3,5,4,7}")
+            .err()
+            .expect("should not parse");
+        assert!(error.to_string().contains("'(1,x)'"));
+        assert!(format!("{error:#}").contains("could not parse light x"));
+
+        let path = std::env::temp_dir().join("aoc-2025-10-parse-errors.txt");
+        std::fs::write(
+            &path,
+            "[.##.] (3) (1,3) {3,5,4,7}\n[.##.] (3) (1,x) {3,5,4,7}\n",
+        )
+        .expect("could not write");
+        let error = parse_file(path.to_str().expect("invalid path"))
+            .err()
+            .expect("should not parse");
+        std::fs::remove_file(&path).expect("could not remove");
+        let message = error.to_string();
+        assert!(message.contains("line 2"));
+        assert!(message.contains("(1,x)"));
+    }
+
+    #[test]
+    fn test_from_matrix() {
+        let machine = Machine::from_matrix(
+            vec![false, true, true, false],
+            vec![
+                vec![false, false, false, true],
+                vec![false, true, false, true],
+                vec![false, false, true, false],
+                vec![false, false, true, true],
+                vec![true, false, true, false],
+                vec![true, true, false, false],
+            ],
+            vec![3, 5, 4, 7],
+        )
+        .expect("could not build");
+        let parsed = parse_line("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}")
+            .expect("could not parse");
+        assert_eq!(machine.target, parsed.target);
+        assert_eq!(machine.buttons, parsed.buttons);
+        assert_eq!(
+            machine
+                .find_shortest_button_press()
+                .expect("could not solve"),
+            parsed
+                .find_shortest_button_press()
+                .expect("could not solve")
+        );
+
+        assert!(Machine::from_matrix(vec![true, false], vec![vec![true]], vec![1, 1]).is_err());
+        assert!(Machine::from_matrix(vec![true, false], vec![vec![true, false]], vec![1]).is_err());
+    }
 }
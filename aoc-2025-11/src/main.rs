@@ -1,30 +1,454 @@
 use anyhow::{Result, anyhow};
 use std::{
-    collections::HashMap,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
     fs::File,
     io::{BufRead, BufReader},
     time::Instant,
 };
 
+enum Visit {
+    InProgress,
+    Done,
+}
+
+#[derive(Debug, PartialEq)]
 struct Network {
     edges: HashMap<String, Vec<String>>,
 }
 
 impl Network {
-    fn paths_count(&self, source: &str, target: &str) -> usize {
+    fn from_edges(edges: HashMap<String, Vec<String>>) -> Network {
+        Network { edges }
+    }
+
+    fn check_acyclic(&self) -> Result<()> {
+        match self.find_cycle() {
+            Some(cycle) => Err(anyhow!("cycle detected: {:?}", cycle)),
+            None => Ok(()),
+        }
+    }
+
+    fn paths_count(&self, source: &str, target: &str) -> Result<usize> {
+        self.check_acyclic()?;
         let mut cache = HashMap::from([(target.to_owned(), 1)]);
-        self.paths_count_cached(source, &mut cache)
+        Ok(self.paths_count_cached(source, &mut cache))
+    }
+
+    //on a cyclic network this counts walks, so max_depth has to stay small
+    #[allow(dead_code)]
+    fn paths_count_limited(&self, source: &str, target: &str, max_depth: usize) -> usize {
+        //no path of an acyclic network is longer than its node count, so the cache keys stay few
+        let max_depth = if self.check_acyclic().is_ok() {
+            max_depth.min(self.node_count())
+        } else {
+            max_depth
+        };
+        let mut cache = HashMap::new();
+        self.paths_count_limited_cached(source, target, max_depth, &mut cache)
     }
 
-    fn paths_count_2(&self) -> usize {
-        let fft_to_dac_count = self.paths_count("fft", "dac");
+    fn paths_count_2(&self) -> Result<usize> {
+        let fft_to_dac_count = self.paths_count("fft", "dac")?;
         if fft_to_dac_count != 0 {
-            self.paths_count("svr", "fft") * fft_to_dac_count * self.paths_count("dac", "out")
+            Ok(self.paths_count("svr", "fft")?
+                * fft_to_dac_count
+                * self.paths_count("dac", "out")?)
         } else {
-            self.paths_count("svr", "dac")
-                * self.paths_count("dac", "fft")
-                * self.paths_count("fft", "out")
+            Ok(self.paths_count("svr", "dac")?
+                * self.paths_count("dac", "fft")?
+                * self.paths_count("fft", "out")?)
+        }
+    }
+
+    #[allow(dead_code)]
+    fn all_paths(&self, source: &str, target: &str, limit: usize) -> Result<Vec<Vec<String>>> {
+        self.check_acyclic()?;
+        let mut paths = vec![];
+        self.all_paths_from(target, limit, &mut vec![source.to_owned()], &mut paths);
+        Ok(paths)
+    }
+
+    fn all_paths_from(
+        &self,
+        target: &str,
+        limit: usize,
+        path: &mut Vec<String>,
+        paths: &mut Vec<Vec<String>>,
+    ) {
+        if paths.len() >= limit {
+            return;
+        }
+        if path.last().is_some_and(|node| node == target) {
+            paths.push(path.clone());
+            return;
+        }
+
+        let Some(nexts) = path.last().and_then(|node| self.edges.get(node)) else {
+            return;
+        };
+        for next in nexts {
+            path.push(next.clone());
+            self.all_paths_from(target, limit, path, paths);
+            path.pop();
+        }
+    }
+
+    #[allow(dead_code)]
+    fn add_edge(&mut self, source: &str, target: &str) {
+        self.edges
+            .entry(source.to_owned())
+            .or_default()
+            .push(target.to_owned());
+    }
+
+    #[allow(dead_code)]
+    fn remove_edge(&mut self, source: &str, target: &str) -> bool {
+        let Some(targets) = self.edges.get_mut(source) else {
+            return false;
+        };
+        let Some(index) = targets.iter().position(|other| other == target) else {
+            return false;
+        };
+        targets.remove(index);
+        true
+    }
+
+    fn node_count(&self) -> usize {
+        self.nodes().len()
+    }
+
+    #[allow(dead_code)]
+    fn edge_count(&self) -> usize {
+        self.edges.values().map(|targets| targets.len()).sum()
+    }
+
+    fn nodes(&self) -> HashSet<&str> {
+        self.edges
+            .iter()
+            .flat_map(|(source, targets)| {
+                std::iter::once(source.as_str()).chain(targets.iter().map(|target| target.as_str()))
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    fn topological_sort(&self) -> Result<Vec<String>> {
+        let mut in_degrees: HashMap<&str, usize> =
+            self.nodes().into_iter().map(|node| (node, 0)).collect();
+        for target in self.edges.values().flatten() {
+            *in_degrees.entry(target).or_default() += 1;
+        }
+
+        let mut sources: Vec<&str> = in_degrees
+            .iter()
+            .filter(|(_, in_degree)| **in_degree == 0)
+            .map(|(node, _)| *node)
+            .collect();
+        sources.sort();
+        let mut queue = VecDeque::from(sources);
+
+        let mut order = Vec::with_capacity(in_degrees.len());
+        while let Some(node) = queue.pop_front() {
+            order.push(node.to_owned());
+            for next in self.edges.get(node).into_iter().flatten() {
+                let in_degree = in_degrees.entry(next).or_default();
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if order.len() < in_degrees.len() {
+            return Err(anyhow!("cycle detected in network"));
+        }
+        Ok(order)
+    }
+
+    #[allow(dead_code)]
+    fn reverse(&self) -> Network {
+        let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+        for (source, targets) in self.edges.iter() {
+            for target in targets {
+                edges
+                    .entry(target.to_owned())
+                    .or_default()
+                    .push(source.to_owned());
+            }
         }
+        Network { edges }
+    }
+
+    //source itself is only included when it can reach itself through a cycle
+    #[allow(dead_code)]
+    fn reachable_from(&self, source: &str) -> HashSet<String> {
+        let mut reachable = HashSet::new();
+        let mut stack = vec![source];
+        while let Some(node) = stack.pop() {
+            for next in self.edges.get(node).into_iter().flatten() {
+                if reachable.insert(next.to_owned()) {
+                    stack.push(next);
+                }
+            }
+        }
+        reachable
+    }
+
+    #[allow(dead_code)]
+    fn shortest_path_length(&self, source: &str, target: &str) -> Option<usize> {
+        let mut visited = HashSet::from([source]);
+        let mut queue = VecDeque::from([(source, 0)]);
+        while let Some((node, depth)) = queue.pop_front() {
+            if node == target {
+                return Some(depth);
+            }
+            for next in self.edges.get(node).into_iter().flatten() {
+                if visited.insert(next) {
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        None
+    }
+
+    //edges missing from weights count as 1
+    #[allow(dead_code)]
+    fn dijkstra(
+        &self,
+        source: &str,
+        weights: &HashMap<(String, String), usize>,
+    ) -> HashMap<String, usize> {
+        let mut distances = HashMap::from([(source.to_owned(), 0)]);
+        let mut heap = BinaryHeap::from([(Reverse(0), source.to_owned())]);
+        while let Some((Reverse(distance), node)) = heap.pop() {
+            if distances.get(&node).is_some_and(|best| *best < distance) {
+                continue;
+            }
+            for next in self.edges.get(&node).into_iter().flatten() {
+                let weight = weights
+                    .get(&(node.clone(), next.clone()))
+                    .copied()
+                    .unwrap_or(1);
+                let next_distance = distance + weight;
+                if distances.get(next).is_none_or(|best| next_distance < *best) {
+                    distances.insert(next.clone(), next_distance);
+                    heap.push((Reverse(next_distance), next.clone()));
+                }
+            }
+        }
+        distances
+    }
+
+    fn find_cycle(&self) -> Option<Vec<String>> {
+        let mut visits: HashMap<&str, Visit> = HashMap::new();
+        let mut sources: Vec<&str> = self.edges.keys().map(|source| source.as_str()).collect();
+        sources.sort();
+
+        for source in sources {
+            if visits.contains_key(source) {
+                continue;
+            }
+            visits.insert(source, Visit::InProgress);
+            let mut stack = vec![(source, 0)];
+
+            while let Some((node, index)) = stack.last_mut() {
+                let next = self
+                    .edges
+                    .get(*node)
+                    .and_then(|targets| targets.get(*index));
+                *index += 1;
+
+                let Some(next) = next else {
+                    visits.insert(node, Visit::Done);
+                    stack.pop();
+                    continue;
+                };
+                match visits.get(next.as_str()) {
+                    Some(Visit::InProgress) => {
+                        let start = stack.iter().position(|(node, _)| node == next)?;
+                        return Some(
+                            stack[start..]
+                                .iter()
+                                .map(|(node, _)| node.to_string())
+                                .collect(),
+                        );
+                    }
+                    Some(Visit::Done) => {}
+                    None => {
+                        visits.insert(next, Visit::InProgress);
+                        stack.push((next, 0));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    //tarjan's algorithm, components are sorted by size descending then by name
+    fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        let mut nodes: Vec<&str> = self.nodes().into_iter().collect();
+        nodes.sort();
+
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        let mut low_links: HashMap<&str, usize> = HashMap::new();
+        let mut on_stack: HashSet<&str> = HashSet::new();
+        let mut component_stack: Vec<&str> = vec![];
+        let mut components: Vec<Vec<String>> = vec![];
+
+        for source in nodes {
+            if indices.contains_key(source) {
+                continue;
+            }
+            indices.insert(source, indices.len());
+            low_links.insert(source, indices[source]);
+            component_stack.push(source);
+            on_stack.insert(source);
+            let mut stack = vec![(source, 0)];
+
+            while let Some((node, index)) = stack.last_mut() {
+                let node = *node;
+                let next = self.edges.get(node).and_then(|targets| targets.get(*index));
+                *index += 1;
+
+                if let Some(next) = next {
+                    let next = next.as_str();
+                    if !indices.contains_key(next) {
+                        indices.insert(next, indices.len());
+                        low_links.insert(next, indices[next]);
+                        component_stack.push(next);
+                        on_stack.insert(next);
+                        stack.push((next, 0));
+                    } else if on_stack.contains(next) {
+                        let low_link = low_links[node].min(indices[next]);
+                        low_links.insert(node, low_link);
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                if let Some((parent, _)) = stack.last() {
+                    let low_link = low_links[parent].min(low_links[node]);
+                    low_links.insert(parent, low_link);
+                }
+                if low_links[node] == indices[node] {
+                    let mut component = vec![];
+                    while let Some(member) = component_stack.pop() {
+                        on_stack.remove(member);
+                        component.push(member.to_owned());
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        components
+    }
+
+    #[allow(dead_code)]
+    fn is_dag(&self) -> bool {
+        self.strongly_connected_components()
+            .iter()
+            .all(|component| component.len() == 1)
+    }
+
+    //brandes' algorithm, normalized by (n-1)*(n-2) as the network is directed
+    fn betweenness_centrality(&self) -> HashMap<String, f64> {
+        let nodes = self.nodes();
+        let mut centrality: HashMap<&str, f64> = nodes.iter().map(|node| (*node, 0.0)).collect();
+
+        for &source in nodes.iter() {
+            let mut order = vec![];
+            let mut predecessors: HashMap<&str, Vec<&str>> = HashMap::new();
+            let mut path_counts: HashMap<&str, f64> = HashMap::from([(source, 1.0)]);
+            let mut distances: HashMap<&str, usize> = HashMap::from([(source, 0)]);
+            let mut queue = VecDeque::from([source]);
+
+            while let Some(node) = queue.pop_front() {
+                order.push(node);
+                let distance = distances[node];
+                for next in self.edges.get(node).into_iter().flatten() {
+                    let next = next.as_str();
+                    if !distances.contains_key(next) {
+                        distances.insert(next, distance + 1);
+                        queue.push_back(next);
+                    }
+                    if distances[next] == distance + 1 {
+                        *path_counts.entry(next).or_default() += path_counts[node];
+                        predecessors.entry(next).or_default().push(node);
+                    }
+                }
+            }
+
+            let mut dependencies: HashMap<&str, f64> = HashMap::new();
+            while let Some(node) = order.pop() {
+                let dependency = dependencies.get(node).copied().unwrap_or(0.0);
+                for &predecessor in predecessors.get(node).into_iter().flatten() {
+                    *dependencies.entry(predecessor).or_default() +=
+                        path_counts[predecessor] / path_counts[node] * (1.0 + dependency);
+                }
+                if node != source {
+                    *centrality.entry(node).or_default() += dependency;
+                }
+            }
+        }
+
+        let n = nodes.len() as f64;
+        let scale = if nodes.len() > 2 {
+            1.0 / ((n - 1.0) * (n - 2.0))
+        } else {
+            1.0
+        };
+        centrality
+            .into_iter()
+            .map(|(node, value)| (node.to_owned(), value * scale))
+            .collect()
+    }
+
+    //ties are broken by taking the smallest name
+    #[allow(dead_code)]
+    fn most_central_node(&self) -> Option<String> {
+        self.betweenness_centrality()
+            .into_iter()
+            .max_by(|(a_node, a_value), (b_node, b_value)| {
+                a_value.total_cmp(b_value).then_with(|| b_node.cmp(a_node))
+            })
+            .map(|(node, _)| node)
+    }
+
+    fn paths_count_limited_cached(
+        &self,
+        origin: &str,
+        target: &str,
+        remaining_depth: usize,
+        cache: &mut HashMap<(String, usize), usize>,
+    ) -> usize {
+        if origin == target {
+            return 1;
+        }
+        if remaining_depth == 0 {
+            return 0;
+        }
+        if let Some(count) = cache.get(&(origin.to_owned(), remaining_depth)) {
+            return *count;
+        }
+
+        let count = self
+            .edges
+            .get(origin)
+            .into_iter()
+            .flatten()
+            .map(|next| self.paths_count_limited_cached(next, target, remaining_depth - 1, cache))
+            .sum();
+
+        cache.insert((origin.to_owned(), remaining_depth), count);
+
+        count
     }
 
     fn paths_count_cached(&self, origin: &str, cache: &mut HashMap<String, usize>) -> usize {
@@ -47,6 +471,23 @@ impl Network {
     }
 }
 
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let quote = |name: &str| format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+
+        let mut sources: Vec<&String> = self.edges.keys().collect();
+        sources.sort();
+
+        writeln!(f, "digraph {{")?;
+        for source in sources {
+            for target in self.edges[source].iter() {
+                writeln!(f, "  {} -> {};", quote(source), quote(target))?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
 fn main() {
     let (part1, part2) = run("./files/input.txt").expect("could not run");
     println!("part1 : {part1}");
@@ -70,23 +511,29 @@ fn run(path: &str) -> Result<(String, String)> {
 }
 
 fn part1(network: &Network) -> usize {
-    network.paths_count("you", "out")
+    network
+        .paths_count("you", "out")
+        .expect("network has a cycle")
 }
 
 fn part2(network: &Network) -> usize {
-    network.paths_count_2()
+    network.paths_count_2().expect("network has a cycle")
 }
 
 fn parse_file(path: &str) -> Result<Network> {
     let file = File::open(path)?;
+    parse(BufReader::new(file))
+}
+
+fn parse<R: BufRead>(reader: R) -> Result<Network> {
     let mut edges = HashMap::new();
-    for line in BufReader::new(file).lines() {
+    for line in reader.lines() {
         let line = line?;
         let (source, targets) = line.split_once(": ").ok_or(anyhow!("did not find colon"))?;
         let targets = targets.split_whitespace().map(|s| s.to_owned()).collect();
         edges.insert(source.to_string(), targets);
     }
-    Ok(Network { edges })
+    Ok(Network::from_edges(edges))
 }
 
 #[cfg(test)]
@@ -106,4 +553,382 @@ mod tests {
         assert_eq!(&part1, "0");
         assert_eq!(&part2, "2");
     }
+
+    #[test]
+    fn test_find_cycle() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            assert_eq!(network.find_cycle(), None);
+        }
+
+        let network = Network::from_edges(HashMap::from([
+            ("a".to_owned(), vec!["b".to_owned()]),
+            ("b".to_owned(), vec!["c".to_owned(), "out".to_owned()]),
+            ("c".to_owned(), vec!["d".to_owned()]),
+            ("d".to_owned(), vec!["b".to_owned()]),
+        ]));
+        let cycle = network.find_cycle().expect("should find a cycle");
+        assert_eq!(cycle, vec!["b", "c", "d"]);
+        assert!(network.paths_count("a", "out").is_err());
+        assert!(network.all_paths("a", "out", 10).is_err());
+    }
+
+    #[test]
+    fn test_all_paths() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            for (source, target) in [("you", "out"), ("svr", "out")] {
+                let paths = network
+                    .all_paths(source, target, 100)
+                    .expect("could not list paths");
+                let count = network
+                    .paths_count(source, target)
+                    .expect("could not count paths");
+                assert_eq!(paths.len(), count);
+                for path in paths.iter() {
+                    assert_eq!(path.first().map(|node| node.as_str()), Some(source));
+                    assert_eq!(path.last().map(|node| node.as_str()), Some(target));
+                    for window in path.windows(2) {
+                        assert!(network.edges[&window[0]].contains(&window[1]));
+                    }
+                }
+            }
+        }
+
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let paths = network
+            .all_paths("you", "out", 2)
+            .expect("could not list paths");
+        assert_eq!(paths.len(), 2);
+
+        for (source, target) in [("you", "out"), ("you", "you")] {
+            let paths = network
+                .all_paths(source, target, 0)
+                .expect("could not list paths");
+            assert!(paths.is_empty());
+        }
+        let paths = network
+            .all_paths("you", "you", 1)
+            .expect("could not list paths");
+        assert_eq!(paths, vec![vec!["you".to_owned()]]);
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            let order = network.topological_sort().expect("could not sort");
+            let positions: HashMap<&str, usize> = order
+                .iter()
+                .enumerate()
+                .map(|(position, node)| (node.as_str(), position))
+                .collect();
+            assert_eq!(order.len(), network.nodes().len());
+            assert_eq!(positions.len(), order.len());
+            for (source, targets) in network.edges.iter() {
+                for target in targets {
+                    assert!(positions[source.as_str()] < positions[target.as_str()]);
+                }
+            }
+        }
+
+        let network = Network::from_edges(HashMap::from([
+            ("a".to_owned(), vec!["b".to_owned()]),
+            ("b".to_owned(), vec!["a".to_owned()]),
+        ]));
+        assert!(network.topological_sort().is_err());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let reversed = network.reverse();
+        assert_eq!(
+            reversed.paths_count("out", "you").expect("could not count"),
+            network.paths_count("you", "out").expect("could not count")
+        );
+
+        let round_trip = reversed.reverse();
+        for (source, target) in [("you", "out"), ("aaa", "out"), ("hhh", "out")] {
+            assert_eq!(
+                round_trip
+                    .paths_count(source, target)
+                    .expect("could not count"),
+                network
+                    .paths_count(source, target)
+                    .expect("could not count")
+            );
+        }
+    }
+
+    #[test]
+    fn test_reachable_from() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            for source in ["you", "svr", "aaa", "fft", "ggg"] {
+                let count = network.paths_count(source, "out").expect("could not count");
+                assert_eq!(
+                    network.reachable_from(source).contains("out"),
+                    count > 0,
+                    "{path} {source}"
+                );
+            }
+        }
+
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        assert!(!network.reachable_from("you").contains("you"));
+        assert_eq!(
+            network.reachable_from("hhh"),
+            HashSet::from(["ccc", "fff", "iii", "ddd", "eee", "ggg", "out"].map(String::from))
+        );
+    }
+
+    #[test]
+    fn test_edit_edges() {
+        let mut network = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(network.node_count(), 11);
+        assert_eq!(network.edge_count(), 17);
+
+        network.add_edge("you", "out");
+        assert_eq!(network.edge_count(), 18);
+        assert_eq!(
+            network.paths_count("you", "out").expect("could not count"),
+            6
+        );
+
+        network.add_edge("new", "you");
+        assert_eq!(network.node_count(), 12);
+        assert_eq!(
+            network.paths_count("new", "out").expect("could not count"),
+            6
+        );
+
+        assert!(network.remove_edge("you", "out"));
+        assert!(network.remove_edge("bbb", "ddd"));
+        assert!(!network.remove_edge("bbb", "ddd"));
+        assert!(!network.remove_edge("zzz", "ddd"));
+        assert_eq!(network.edge_count(), 17);
+        assert_eq!(
+            network.paths_count("you", "out").expect("could not count"),
+            4
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let dot = network.to_string();
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.ends_with('}'));
+        assert_eq!(dot.matches("->").count(), network.edge_count());
+        assert!(dot.contains("  \"you\" -> \"bbb\";\n"));
+
+        let mut network = Network::from_edges(HashMap::new());
+        network.add_edge("a \"b\"", "c");
+        assert_eq!(
+            network.to_string(),
+            "digraph {\n  \"a \\\"b\\\"\" -> \"c\";\n}"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let content = std::fs::read_to_string(path).expect("could not read");
+            assert_eq!(
+                parse(std::io::Cursor::new(content)).expect("could not parse"),
+                parse_file(path).expect("could not parse")
+            );
+        }
+
+        let network = parse(std::io::Cursor::new(b"a: b c\nb: c\n")).expect("could not parse");
+        assert_eq!(
+            network,
+            Network::from_edges(HashMap::from([
+                ("a".to_owned(), vec!["b".to_owned(), "c".to_owned()]),
+                ("b".to_owned(), vec!["c".to_owned()]),
+            ]))
+        );
+        assert!(parse(std::io::Cursor::new(b"a b c\n")).is_err());
+    }
+
+    #[test]
+    fn test_shortest_path_length() {
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(network.shortest_path_length("you", "out"), Some(3));
+        assert_eq!(network.shortest_path_length("you", "you"), Some(0));
+        assert_eq!(network.shortest_path_length("out", "you"), None);
+
+        let network = parse_file("./files/test2.txt").expect("could not parse");
+        assert_eq!(network.shortest_path_length("you", "out"), None);
+        assert_eq!(network.shortest_path_length("svr", "out"), Some(8));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            let components = network.strongly_connected_components();
+            assert_eq!(components.len(), network.node_count());
+            assert!(components.iter().all(|component| component.len() == 1));
+            assert!(network.is_dag());
+        }
+
+        let network = Network::from_edges(HashMap::from([
+            ("a".to_owned(), vec!["b".to_owned()]),
+            ("b".to_owned(), vec!["c".to_owned(), "out".to_owned()]),
+            ("c".to_owned(), vec!["d".to_owned()]),
+            ("d".to_owned(), vec!["b".to_owned(), "e".to_owned()]),
+            ("e".to_owned(), vec!["out".to_owned()]),
+        ]));
+        let components = network.strongly_connected_components();
+        assert_eq!(
+            components,
+            vec![
+                vec!["b".to_owned(), "c".to_owned(), "d".to_owned()],
+                vec!["a".to_owned()],
+                vec!["e".to_owned()],
+                vec!["out".to_owned()],
+            ]
+        );
+        assert!(!network.is_dag());
+    }
+
+    #[test]
+    fn test_betweenness_centrality() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            let centrality = network.betweenness_centrality();
+            assert_eq!(centrality.len(), network.node_count());
+            for value in centrality.values() {
+                assert!((0.0..=1.0).contains(value), "{path} {value}");
+            }
+        }
+
+        //every path from the first 5 nodes to the last 8 goes through ccc
+        let network = parse_file("./files/test2.txt").expect("could not parse");
+        let centrality = network.betweenness_centrality();
+        assert!((centrality["ccc"] - 40.0 / (13.0 * 12.0)).abs() < 1e-9);
+        assert_eq!(centrality["svr"], 0.0);
+        assert_eq!(centrality["out"], 0.0);
+        assert_eq!(network.most_central_node(), Some("ccc".to_owned()));
+
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(network.most_central_node(), Some("ccc".to_owned()));
+
+        let network = Network::from_edges(HashMap::from([
+            ("a".to_owned(), vec!["hub".to_owned()]),
+            ("b".to_owned(), vec!["hub".to_owned()]),
+            ("hub".to_owned(), vec!["c".to_owned(), "d".to_owned()]),
+        ]));
+        let centrality = network.betweenness_centrality();
+        assert!((centrality["hub"] - 4.0 / 12.0).abs() < 1e-9);
+        assert_eq!(network.most_central_node(), Some("hub".to_owned()));
+        assert_eq!(
+            Network::from_edges(HashMap::new()).most_central_node(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            for source in ["you", "svr", "aaa", "ccc"] {
+                let distances = network.dijkstra(source, &HashMap::new());
+                for node in network.nodes() {
+                    assert_eq!(
+                        distances.get(node).copied(),
+                        network.shortest_path_length(source, node),
+                        "{path} {source} {node}"
+                    );
+                }
+            }
+        }
+
+        let network = Network::from_edges(HashMap::from([
+            ("a".to_owned(), vec!["b".to_owned(), "c".to_owned()]),
+            ("b".to_owned(), vec!["d".to_owned()]),
+            ("c".to_owned(), vec!["b".to_owned(), "d".to_owned()]),
+            ("d".to_owned(), vec!["e".to_owned()]),
+        ]));
+        let weights = HashMap::from([
+            (("a".to_owned(), "b".to_owned()), 10),
+            (("a".to_owned(), "c".to_owned()), 2),
+            (("c".to_owned(), "b".to_owned()), 3),
+            (("c".to_owned(), "d".to_owned()), 9),
+            (("b".to_owned(), "d".to_owned()), 2),
+        ]);
+        let distances = network.dijkstra("a", &weights);
+        assert_eq!(
+            distances,
+            HashMap::from([
+                ("a".to_owned(), 0),
+                ("b".to_owned(), 5),
+                ("c".to_owned(), 2),
+                ("d".to_owned(), 7),
+                ("e".to_owned(), 8),
+            ])
+        );
+        assert_eq!(
+            network.dijkstra("d", &weights),
+            HashMap::from([("d".to_owned(), 0), ("e".to_owned(), 1)])
+        );
+    }
+
+    #[test]
+    fn test_paths_count_limited() {
+        for path in ["./files/test.txt", "./files/test2.txt"] {
+            let network = parse_file(path).expect("could not parse");
+            for (source, target) in [
+                ("you", "out"),
+                ("svr", "out"),
+                ("aaa", "out"),
+                ("ccc", "ccc"),
+            ] {
+                let count = network
+                    .paths_count(source, target)
+                    .expect("could not count");
+                assert_eq!(
+                    network.paths_count_limited(source, target, usize::MAX),
+                    count
+                );
+                assert_eq!(
+                    network.paths_count_limited(source, target, 0),
+                    (source == target) as usize
+                );
+                let counts: Vec<usize> = (0..=network.node_count())
+                    .map(|depth| network.paths_count_limited(source, target, depth))
+                    .collect();
+                assert!(counts.windows(2).all(|pair| pair[0] <= pair[1]));
+                assert_eq!(counts.last(), Some(&count));
+            }
+        }
+
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let counts: Vec<usize> = (0..5)
+            .map(|depth| network.paths_count_limited("you", "out", depth))
+            .collect();
+        assert_eq!(counts, vec![0, 0, 0, 3, 5]);
+    }
+
+    #[test]
+    fn test_paths_count_limited_deep() {
+        //a ladder where every rung doubles the number of paths
+        let mut network = Network::from_edges(HashMap::new());
+        for rung in 0..60 {
+            network.add_edge(&format!("a{rung}"), &format!("a{}", rung + 1));
+            network.add_edge(&format!("a{rung}"), &format!("b{}", rung + 1));
+            network.add_edge(&format!("b{rung}"), &format!("a{}", rung + 1));
+            network.add_edge(&format!("b{rung}"), &format!("b{}", rung + 1));
+        }
+        assert_eq!(
+            network.paths_count_limited("a0", "a60", usize::MAX),
+            1 << 59
+        );
+        assert_eq!(
+            network.paths_count("a0", "a60").expect("could not count"),
+            1 << 59
+        );
+    }
 }
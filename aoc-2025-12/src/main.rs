@@ -1,6 +1,11 @@
 use anyhow::{Result, anyhow};
-use std::time::Instant;
+use rayon::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Problem {
     width: usize,
     height: usize,
@@ -14,6 +19,29 @@ impl Problem {
         self.piece_counts.iter().sum::<usize>() <= width_3 * height_3
     }
 
+    fn coverage_ratio(&self, piece_sizes: &[usize]) -> f64 {
+        let pieces_cells: usize = piece_sizes
+            .iter()
+            .zip(self.piece_counts.iter())
+            .map(|(size, count)| size * count)
+            .sum();
+        pieces_cells as f64 / (self.width * self.height) as f64
+    }
+
+    //lower bound on the number of pieces needed to cover the whole grid, 0 without any piece size
+    #[allow(dead_code)]
+    fn min_pieces_needed(&self, max_piece_size: usize) -> usize {
+        if max_piece_size == 0 {
+            return 0;
+        }
+        (self.width * self.height).div_ceil(max_piece_size)
+    }
+
+    #[allow(dead_code)]
+    fn fits_exactly(&self, piece_sizes: &[usize]) -> bool {
+        self.coverage_ratio(piece_sizes) == 1.0
+    }
+
     fn definitely_does_not_fit(&self, piece_sizes: &[usize]) -> bool {
         let cells_count = self.width * self.height;
 
@@ -26,20 +54,139 @@ impl Problem {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq)]
 struct ProblemResult {
     fit: usize,
     does_not_fit: usize,
     unknown: usize,
 }
 
+impl ProblemResult {
+    fn record(&mut self, problem: &Problem, piece_sizes: &[usize]) {
+        if problem.definitely_fits() {
+            self.fit += 1;
+        } else if problem.definitely_does_not_fit(piece_sizes) {
+            self.does_not_fit += 1;
+        } else {
+            self.unknown += 1;
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct GridStats {
+    min_width: f64,
+    max_width: f64,
+    mean_width: f64,
+    min_height: f64,
+    max_height: f64,
+    mean_height: f64,
+}
+
 struct Problems {
     piece_sizes: Vec<usize>,
+    pieces: Vec<Vec<(usize, usize)>>,
     problems: Vec<Problem>,
 }
 
 impl Problems {
     fn part1(&self) -> ProblemResult {
+        let mut result = ProblemResult::default();
+        for problem in self.problems.iter() {
+            result.record(problem, &self.piece_sizes);
+        }
+        result
+    }
+
+    #[allow(dead_code)]
+    fn part1_parallel(&self) -> ProblemResult {
+        self.problems
+            .par_iter()
+            .fold(ProblemResult::default, |mut result, problem| {
+                result.record(problem, &self.piece_sizes);
+                result
+            })
+            .reduce(ProblemResult::default, |a, b| ProblemResult {
+                fit: a.fit + b.fit,
+                does_not_fit: a.does_not_fit + b.does_not_fit,
+                unknown: a.unknown + b.unknown,
+            })
+    }
+
+    #[allow(dead_code)]
+    fn sort_by_difficulty(&mut self) {
+        let piece_sizes = &self.piece_sizes;
+        let category = |problem: &Problem| {
+            if problem.definitely_fits() {
+                0
+            } else if problem.definitely_does_not_fit(piece_sizes) {
+                2
+            } else {
+                1
+            }
+        };
+        self.problems.sort_by(|a, b| {
+            category(a).cmp(&category(b)).then_with(|| {
+                a.coverage_ratio(piece_sizes)
+                    .total_cmp(&b.coverage_ratio(piece_sizes))
+            })
+        });
+    }
+
+    fn total_unknown_count(&self) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| {
+                !problem.definitely_fits() && !problem.definitely_does_not_fit(&self.piece_sizes)
+            })
+            .count()
+    }
+
+    #[allow(dead_code)]
+    fn is_all_resolved(&self) -> bool {
+        self.total_unknown_count() == 0
+    }
+
+    #[allow(dead_code)]
+    fn deduplicate(&mut self) -> usize {
+        let count = self.problems.len();
+        let mut seen = HashSet::new();
+        self.problems.retain(|problem| seen.insert(problem.clone()));
+        count - self.problems.len()
+    }
+
+    #[allow(dead_code)]
+    fn group_by_grid_size(&self) -> HashMap<(usize, usize), Vec<usize>> {
+        let mut groups: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (index, problem) in self.problems.iter().enumerate() {
+            groups
+                .entry((problem.width, problem.height))
+                .or_default()
+                .push(index);
+        }
+        groups
+    }
+
+    #[allow(dead_code)]
+    fn grid_statistics(&self) -> GridStats {
+        if self.problems.is_empty() {
+            return GridStats::default();
+        }
+        let count = self.problems.len() as f64;
+        let widths = self.problems.iter().map(|problem| problem.width as f64);
+        let heights = self.problems.iter().map(|problem| problem.height as f64);
+        GridStats {
+            min_width: widths.clone().fold(f64::INFINITY, f64::min),
+            max_width: widths.clone().fold(0.0, f64::max),
+            mean_width: widths.sum::<f64>() / count,
+            min_height: heights.clone().fold(f64::INFINITY, f64::min),
+            max_height: heights.clone().fold(0.0, f64::max),
+            mean_height: heights.sum::<f64>() / count,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn part1_exact(&self) -> ProblemResult {
         let mut result = ProblemResult::default();
         for problem in self.problems.iter() {
             if problem.definitely_fits() {
@@ -47,13 +194,199 @@ impl Problems {
             } else if problem.definitely_does_not_fit(&self.piece_sizes) {
                 result.does_not_fit += 1;
             } else {
-                result.unknown += 1;
+                match solve_exact(problem, &self.pieces) {
+                    Some(true) => result.fit += 1,
+                    Some(false) => result.does_not_fit += 1,
+                    None => result.unknown += 1,
+                }
             }
         }
         result
     }
 }
 
+struct Orientation {
+    //one bit per column for each row of the piece, shifted so the leftmost column is bit 0
+    rows: Vec<u128>,
+    //column of the first cell of the top row, which is placed on the first empty cell
+    anchor_col: usize,
+}
+
+fn orientations(piece: &[(usize, usize)]) -> Vec<Orientation> {
+    let mut shapes: Vec<Vec<(isize, isize)>> = vec![];
+    for flip in [false, true] {
+        for rotation in 0..4 {
+            let mut cells: Vec<(isize, isize)> = piece
+                .iter()
+                .map(|(row, col)| {
+                    let (mut row, mut col) = (*row as isize, *col as isize);
+                    if flip {
+                        col = -col;
+                    }
+                    for _ in 0..rotation {
+                        (row, col) = (col, -row);
+                    }
+                    (row, col)
+                })
+                .collect();
+            let min_row = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+            let min_col = cells.iter().map(|(_, col)| *col).min().unwrap_or(0);
+            for cell in cells.iter_mut() {
+                *cell = (cell.0 - min_row, cell.1 - min_col);
+            }
+            cells.sort();
+            if !shapes.contains(&cells) {
+                shapes.push(cells);
+            }
+        }
+    }
+    shapes
+        .into_iter()
+        .map(|cells| {
+            let row_count = cells
+                .iter()
+                .map(|(row, _)| *row as usize + 1)
+                .max()
+                .unwrap_or(0);
+            let mut rows = vec![0; row_count];
+            for (row, col) in cells.iter() {
+                rows[*row as usize] |= 1 << col;
+            }
+            Orientation {
+                rows,
+                anchor_col: cells.first().map_or(0, |(_, col)| *col as usize),
+            }
+        })
+        .collect()
+}
+
+//rows are stored as u128, so None when neither side of the grid fits in one
+fn solve_exact(problem: &Problem, pieces: &[Vec<(usize, usize)>]) -> Option<bool> {
+    if problem.width > 128 {
+        if problem.height > 128 {
+            return None;
+        }
+        //pieces can be flipped and rotated, so the transposed grid has the same answer
+        let transposed = Problem {
+            width: problem.height,
+            height: problem.width,
+            piece_counts: problem.piece_counts.clone(),
+        };
+        return solve_exact(&transposed, pieces);
+    }
+    let needed_cells: usize = pieces
+        .iter()
+        .zip(problem.piece_counts.iter())
+        .map(|(piece, count)| piece.len() * count)
+        .sum();
+    let Some(spare_cells) = (problem.width * problem.height).checked_sub(needed_cells) else {
+        return Some(false);
+    };
+    let orientations: Vec<_> = pieces.iter().map(|piece| orientations(piece)).collect();
+    let min_piece_rows = orientations
+        .iter()
+        .zip(problem.piece_counts.iter())
+        .filter(|(_, count)| **count > 0)
+        .flat_map(|(orientations, _)| orientations.iter())
+        .map(|orientation| orientation.rows.len())
+        .min()
+        .unwrap_or(0);
+    let mut solver = Solver {
+        grid: vec![0; problem.height],
+        full_row: u128::MAX
+            .checked_shr((128 - problem.width) as u32)
+            .unwrap_or(0),
+        orientations,
+        remaining: problem.piece_counts.clone(),
+        min_piece_rows,
+    };
+    Some(solver.fill(0, spare_cells))
+}
+
+struct Solver {
+    //one bit per filled cell on each row
+    grid: Vec<u128>,
+    full_row: u128,
+    orientations: Vec<Vec<Orientation>>,
+    remaining: Vec<usize>,
+    min_piece_rows: usize,
+}
+
+impl Solver {
+    fn placement(&self, row: usize, col: usize, orientation: &Orientation) -> Option<usize> {
+        let shift = col.checked_sub(orientation.anchor_col)?;
+        if row + orientation.rows.len() > self.grid.len() {
+            return None;
+        }
+        orientation
+            .rows
+            .iter()
+            .enumerate()
+            .all(|(d_row, mask)| {
+                let shifted = mask << shift;
+                shifted >> shift == *mask
+                    && shifted & !self.full_row == 0
+                    && shifted & self.grid[row + d_row] == 0
+            })
+            .then_some(shift)
+    }
+
+    fn toggle(&mut self, row: usize, shift: usize, piece: usize, orientation: usize) {
+        for (d_row, mask) in self.orientations[piece][orientation]
+            .rows
+            .iter()
+            .enumerate()
+        {
+            self.grid[row + d_row] ^= mask << shift;
+        }
+    }
+
+    fn fill(&mut self, row: usize, spare_cells: usize) -> bool {
+        if self.remaining.iter().all(|count| *count == 0) {
+            return true;
+        }
+        let Some(row) = (row..self.grid.len()).find(|row| self.grid[*row] != self.full_row) else {
+            return false;
+        };
+        //no piece can be anchored this low anymore
+        if row + self.min_piece_rows > self.grid.len() {
+            return false;
+        }
+        let col = (!self.grid[row]).trailing_zeros() as usize;
+
+        for piece in 0..self.remaining.len() {
+            if self.remaining[piece] == 0 {
+                continue;
+            }
+            for orientation in 0..self.orientations[piece].len() {
+                let Some(shift) = self.placement(row, col, &self.orientations[piece][orientation])
+                else {
+                    continue;
+                };
+                self.toggle(row, shift, piece, orientation);
+                self.remaining[piece] -= 1;
+                let solved = self.fill(row, spare_cells);
+                self.remaining[piece] += 1;
+                self.toggle(row, shift, piece, orientation);
+                if solved {
+                    return true;
+                }
+            }
+        }
+
+        //leave the cell empty, as long as enough cells remain for the pieces
+        if spare_cells > 0 {
+            self.grid[row] |= 1 << col;
+            let solved = self.fill(row, spare_cells - 1);
+            self.grid[row] &= !(1 << col);
+            if solved {
+                return true;
+            }
+        }
+        false
+    }
+}
+
 fn main() {
     let (part1, part2) = run("./files/input.txt").expect("could not run");
     println!("part1 : {part1}");
@@ -87,12 +420,15 @@ fn part2() -> u128 {
 fn parse_file(path: &str) -> Result<Problems> {
     let text = std::fs::read_to_string(path)?;
     let mut piece_sizes = vec![];
+    let mut pieces = vec![];
     let mut problems = vec![];
     for part in text.split("\n\n") {
         if part.contains("#") {
             piece_sizes.push(parse_piece_part(part));
+            pieces.push(parse_piece_shape(part));
         } else {
-            for line in part.lines() {
+            //csv files start with a header line
+            for line in part.lines().filter(|line| !line.starts_with("width,")) {
                 problems.push(parse_grid_line(line)?);
             }
         }
@@ -100,6 +436,7 @@ fn parse_file(path: &str) -> Result<Problems> {
 
     Ok(Problems {
         piece_sizes,
+        pieces,
         problems,
     })
 }
@@ -108,23 +445,96 @@ fn parse_piece_part(part: &str) -> usize {
     part.chars().filter(|ch| *ch == '#').count()
 }
 
+fn parse_piece_shape(part: &str) -> Vec<(usize, usize)> {
+    part.lines()
+        .skip(1)
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, ch)| *ch == '#')
+                .map(move |(col, _)| (row, col))
+        })
+        .collect()
+}
+
 fn parse_grid_line(line: &str) -> Result<Problem> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        parse_grid_json(line)
+    } else if line.contains(',') && !line.contains('x') {
+        parse_grid_csv(line)
+    } else {
+        parse_grid_dimensions(line)
+    }
+}
+
+fn parse_usize(s: &str) -> Result<usize> {
+    s.trim()
+        .parse::<usize>()
+        .map_err(|_| anyhow!("could not parse as usize {}", s))
+}
+
+fn parse_grid_csv(line: &str) -> Result<Problem> {
+    let values = line
+        .split(',')
+        .map(parse_usize)
+        .collect::<Result<Vec<_>>>()?;
+    if values.len() < 2 {
+        return Err(anyhow!("missing dimensions in {line}"));
+    }
+    Ok(Problem {
+        width: values[0],
+        height: values[1],
+        piece_counts: values[2..].to_vec(),
+    })
+}
+
+fn parse_grid_json(line: &str) -> Result<Problem> {
+    let body: String = line.chars().filter(|ch| !ch.is_whitespace()).collect();
+    let body = body
+        .strip_prefix('{')
+        .and_then(|body| body.strip_suffix('}'))
+        .ok_or(anyhow!("not a json object {line}"))?;
+    let (before, after) = body
+        .split_once("\"c\":[")
+        .ok_or(anyhow!("missing counts in {line}"))?;
+    let (counts, rest) = after
+        .split_once(']')
+        .ok_or(anyhow!("unclosed counts in {line}"))?;
+    let piece_counts = counts
+        .split(',')
+        .filter(|count| !count.is_empty())
+        .map(parse_usize)
+        .collect::<Result<Vec<_>>>()?;
+
+    let (mut width, mut height) = (None, None);
+    for field in before.split(',').chain(rest.split(',')) {
+        match field.split_once(':') {
+            Some(("\"w\"", value)) => width = Some(parse_usize(value)?),
+            Some(("\"h\"", value)) => height = Some(parse_usize(value)?),
+            None if field.is_empty() => {}
+            _ => return Err(anyhow!("unexpected field {field} in {line}")),
+        }
+    }
+    Ok(Problem {
+        width: width.ok_or(anyhow!("missing width in {line}"))?,
+        height: height.ok_or(anyhow!("missing height in {line}"))?,
+        piece_counts,
+    })
+}
+
+fn parse_grid_dimensions(line: &str) -> Result<Problem> {
     let (dimensions, counts) = line
         .split_once(": ")
         .ok_or(anyhow!("could not split on :"))?;
     let dimensions = dimensions
         .split("x")
-        .map(|s| {
-            s.parse::<usize>()
-                .map_err(|_| anyhow!("could not parse as usize {}", s))
-        })
+        .map(parse_usize)
         .collect::<Result<Vec<_>>>()?;
     let counts = counts
         .split_whitespace()
-        .map(|s| {
-            s.parse::<usize>()
-                .map_err(|_| anyhow!("could not parse as usize {}", s))
-        })
+        .map(parse_usize)
         .collect::<Result<Vec<_>>>()?;
 
     Ok(Problem {
@@ -133,3 +543,285 @@ fn parse_grid_line(line: &str) -> Result<Problem> {
         piece_counts: counts,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part() {
+        let (part1, _) = run("./files/test.txt").expect("could not run");
+        assert_eq!(
+            &part1,
+            "ProblemResult { fit: 0, does_not_fit: 0, unknown: 3 }"
+        );
+    }
+
+    #[test]
+    fn test_solve_exact() {
+        let problems = parse_file("./files/test.txt").expect("could not parse");
+        //every problem is unknown to the heuristics, the last one does not fit
+        let result = problems.part1_exact();
+        assert_eq!((result.fit, result.does_not_fit, result.unknown), (2, 1, 0));
+        assert_eq!(
+            solve_exact(&problems.problems[0], &problems.pieces),
+            Some(true)
+        );
+
+        //agrees with the heuristics when they apply
+        let fits = Problem {
+            width: 6,
+            height: 3,
+            piece_counts: vec![1, 0, 0, 0, 0, 1],
+        };
+        assert!(fits.definitely_fits());
+        assert_eq!(solve_exact(&fits, &problems.pieces), Some(true));
+        let too_small = Problem {
+            width: 3,
+            height: 3,
+            piece_counts: vec![1, 1, 0, 0, 0, 0],
+        };
+        assert!(too_small.definitely_does_not_fit(&problems.piece_sizes));
+        assert_eq!(solve_exact(&too_small, &problems.pieces), Some(false));
+
+        //grids wider than a row are transposed, and skipped when both sides are too long
+        for (width, height) in [(200, 3), (3, 200)] {
+            let long = Problem {
+                width,
+                height,
+                piece_counts: vec![2, 0, 0, 0, 0, 0],
+            };
+            assert_eq!(solve_exact(&long, &problems.pieces), Some(true));
+        }
+        let huge = Problem {
+            width: 130,
+            height: 130,
+            piece_counts: vec![1900, 0, 0, 0, 0, 0],
+        };
+        assert!(!huge.definitely_fits());
+        assert!(!huge.definitely_does_not_fit(&problems.piece_sizes));
+        assert_eq!(solve_exact(&huge, &problems.pieces), None);
+
+        let problems = Problems {
+            problems: vec![huge],
+            ..problems
+        };
+        let result = problems.part1_exact();
+        assert_eq!((result.fit, result.does_not_fit, result.unknown), (0, 0, 1));
+    }
+
+    #[test]
+    fn test_coverage_ratio() {
+        let problems = parse_file("./files/test.txt").expect("could not parse");
+        let ratios: Vec<f64> = problems
+            .problems
+            .iter()
+            .map(|problem| problem.coverage_ratio(&problems.piece_sizes))
+            .collect();
+        assert_eq!(ratios, vec![14.0 / 16.0, 42.0 / 60.0, 49.0 / 60.0]);
+        assert!(
+            problems
+                .problems
+                .iter()
+                .all(|problem| !problem.fits_exactly(&problems.piece_sizes))
+        );
+
+        let exact = Problem {
+            width: 7,
+            height: 2,
+            piece_counts: vec![1, 1],
+        };
+        assert!(exact.fits_exactly(&problems.piece_sizes));
+
+        for width in 0..8 {
+            for count in 0..4 {
+                let problem = Problem {
+                    width,
+                    height: 3,
+                    piece_counts: vec![count, 1],
+                };
+                let ratio = problem.coverage_ratio(&problems.piece_sizes);
+                assert!(ratio >= 0.0);
+                if problem.definitely_does_not_fit(&problems.piece_sizes) {
+                    assert!(ratio > 1.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_groups_and_statistics() {
+        let problems = parse_file("./files/test.txt").expect("could not parse");
+        let groups = problems.group_by_grid_size();
+        assert_eq!(
+            groups,
+            HashMap::from([((4, 4), vec![0]), ((12, 5), vec![1, 2])])
+        );
+        let mut indices: Vec<usize> = groups.into_values().flatten().collect();
+        indices.sort();
+        assert_eq!(indices, (0..problems.problems.len()).collect::<Vec<_>>());
+
+        assert_eq!(
+            problems.grid_statistics(),
+            GridStats {
+                min_width: 4.0,
+                max_width: 12.0,
+                mean_width: 28.0 / 3.0,
+                min_height: 4.0,
+                max_height: 5.0,
+                mean_height: 14.0 / 3.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_part1_parallel() {
+        let mut problems = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(problems.part1_parallel(), problems.part1());
+
+        problems.problems = (0..1000)
+            .map(|index| Problem {
+                width: 3 + index % 17,
+                height: 3 + index % 11,
+                piece_counts: vec![index % 5, index % 3, 1, 0, index % 7, 2],
+            })
+            .collect();
+        let result = problems.part1_parallel();
+        assert_eq!(result, problems.part1());
+        assert!(result.fit > 0 && result.does_not_fit > 0 && result.unknown > 0);
+    }
+
+    #[test]
+    fn test_min_pieces_needed() {
+        let problems = parse_file("./files/test.txt").expect("could not parse");
+        let max_piece_size = *problems.piece_sizes.iter().max().unwrap();
+        let bounds: Vec<usize> = problems
+            .problems
+            .iter()
+            .map(|problem| problem.min_pieces_needed(max_piece_size))
+            .collect();
+        assert_eq!(bounds, vec![3, 9, 9]);
+
+        //the grid does not need to be covered, so fewer pieces can still fit
+        let problem = &problems.problems[0];
+        assert!(problem.piece_counts.iter().sum::<usize>() < bounds[0]);
+        assert_eq!(solve_exact(problem, &problems.pieces), Some(true));
+
+        //covering the grid exactly needs at least that many pieces
+        let exact = Problem {
+            width: 7,
+            height: 2,
+            piece_counts: vec![1, 1],
+        };
+        assert!(exact.fits_exactly(&problems.piece_sizes));
+        assert!(
+            exact.piece_counts.iter().sum::<usize>() >= exact.min_pieces_needed(max_piece_size)
+        );
+        assert_eq!(exact.min_pieces_needed(0), 0);
+    }
+
+    #[test]
+    fn test_deduplicate() {
+        let mut problems = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(problems.deduplicate(), 0);
+
+        let problem = |width, height, piece_counts: Vec<usize>| Problem {
+            width,
+            height,
+            piece_counts,
+        };
+        problems.problems = vec![
+            problem(9, 9, vec![1, 1, 1, 1, 1, 1]),
+            problem(4, 4, vec![0, 0, 0, 0, 2, 0]),
+            problem(9, 9, vec![1, 1, 1, 1, 1, 1]),
+            problem(2, 2, vec![1, 0, 0, 0, 0, 0]),
+            problem(4, 4, vec![0, 0, 0, 0, 2, 0]),
+            problem(9, 9, vec![1, 1, 1, 1, 1, 1]),
+            problem(4, 4, vec![0, 0, 0, 0, 0, 2]),
+        ];
+        let unique_result = Problems {
+            piece_sizes: problems.piece_sizes.clone(),
+            pieces: problems.pieces.clone(),
+            problems: vec![
+                problems.problems[0].clone(),
+                problems.problems[1].clone(),
+                problems.problems[3].clone(),
+                problems.problems[6].clone(),
+            ],
+        }
+        .part1();
+
+        assert_eq!(problems.deduplicate(), 3);
+        assert_eq!(problems.problems.len(), 4);
+        assert_eq!(problems.problems[2], problem(2, 2, vec![1, 0, 0, 0, 0, 0]));
+        assert_eq!(problems.part1(), unique_result);
+        assert_eq!(problems.deduplicate(), 0);
+    }
+
+    #[test]
+    fn test_parse_grid_formats() {
+        let expected = Problem {
+            width: 12,
+            height: 5,
+            piece_counts: vec![1, 0, 1, 0, 2, 2],
+        };
+        assert_eq!(parse_grid_line("12x5: 1 0 1 0 2 2").unwrap(), expected);
+        assert_eq!(parse_grid_line("12,5,1,0,1,0,2,2").unwrap(), expected);
+        assert_eq!(
+            parse_grid_line(r#"{"w":12,"h":5,"c":[1,0,1,0,2,2]}"#).unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_grid_line(r#"{ "c": [1, 0, 1, 0, 2, 2], "h": 5, "w": 12 }"#).unwrap(),
+            expected
+        );
+
+        assert!(parse_grid_line("12,a,1").is_err());
+        assert!(parse_grid_line(r#"{"w":12,"c":[1]}"#).is_err());
+        assert!(parse_grid_line(r#"{"w":12,"h":5,"d":1,"c":[1]}"#).is_err());
+    }
+
+    #[test]
+    fn test_sort_by_difficulty() {
+        let mut problems = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(problems.total_unknown_count(), problems.part1().unknown);
+        assert!(!problems.is_all_resolved());
+
+        problems.problems = (0..200)
+            .map(|index| Problem {
+                width: 3 + index % 13,
+                height: 3 + index % 7,
+                piece_counts: vec![index % 4, index % 3, 1, 0, index % 5, 1],
+            })
+            .collect();
+        let unknown = problems.total_unknown_count();
+        assert_eq!(unknown, problems.part1().unknown);
+        problems.sort_by_difficulty();
+        assert_eq!(problems.total_unknown_count(), unknown);
+
+        let piece_sizes = &problems.piece_sizes;
+        let fits = problems
+            .problems
+            .iter()
+            .take_while(|problem| problem.definitely_fits())
+            .count();
+        let does_not_fit = problems
+            .problems
+            .iter()
+            .rev()
+            .take_while(|problem| problem.definitely_does_not_fit(piece_sizes))
+            .count();
+        let result = problems.part1();
+        assert_eq!((fits, does_not_fit), (result.fit, result.does_not_fit));
+        let ratios: Vec<f64> = problems.problems[fits..fits + unknown]
+            .iter()
+            .map(|problem| problem.coverage_ratio(piece_sizes))
+            .collect();
+        assert!(ratios.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        problems
+            .problems
+            .retain(|problem| problem.definitely_fits());
+        assert!(problems.is_all_resolved());
+    }
+}
@@ -13,11 +13,136 @@ fn main() {
     println!("part2 : {part2}");
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct IdRange {
     begin: u64,
     end: u64,
 }
 
+impl IdRange {
+    //None when the length does not fit in a usize, so that the iterator length is exact
+    #[allow(dead_code)]
+    fn iter(&self) -> Option<IdRangeIter> {
+        usize::try_from(self.len()?).ok()?;
+        Some(IdRangeIter {
+            next: self.begin,
+            end: self.end,
+            done: self.begin > self.end,
+        })
+    }
+
+    #[allow(dead_code)]
+    fn contains(&self, id: u64) -> bool {
+        id >= self.begin && id <= self.end
+    }
+
+    //None for the full u64 range, whose length does not fit in a u64
+    fn len(&self) -> Option<u64> {
+        (self.end - self.begin).checked_add(1)
+    }
+
+    #[allow(dead_code)]
+    fn split_at(&self, id: u64) -> (Option<IdRange>, Option<IdRange>) {
+        let before = (id > self.begin).then(|| IdRange {
+            begin: self.begin,
+            end: self.end.min(id - 1),
+        });
+        let after = (id < self.end).then(|| IdRange {
+            begin: self.begin.max(id + 1),
+            end: self.end,
+        });
+        (before, after)
+    }
+
+    #[allow(dead_code)]
+    fn overlap(&self, other: &IdRange) -> Option<IdRange> {
+        let begin = self.begin.max(other.begin);
+        let end = self.end.min(other.end);
+        (begin <= end).then_some(IdRange { begin, end })
+    }
+
+    fn gap_to(&self, other: &IdRange) -> Option<IdRange> {
+        let begin = self.end.min(other.end).checked_add(1)?;
+        let end = self.begin.max(other.begin).checked_sub(1)?;
+        (begin <= end).then_some(IdRange { begin, end })
+    }
+
+    #[allow(dead_code)]
+    fn union_if_adjacent(&self, other: &IdRange) -> Option<IdRange> {
+        if self.gap_to(other).is_some() {
+            return None;
+        }
+        Some(IdRange {
+            begin: self.begin.min(other.begin),
+            end: self.end.max(other.end),
+        })
+    }
+}
+
+impl std::str::FromStr for IdRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<IdRange> {
+        parse_range(s.as_bytes())
+    }
+}
+
+impl std::fmt::Display for IdRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.begin, self.end)
+    }
+}
+
+#[cfg(test)]
+impl proptest::arbitrary::Arbitrary for IdRange {
+    //upper bound for the generated ids, u64::MAX when None
+    type Parameters = Option<u64>;
+    type Strategy = proptest::strategy::BoxedStrategy<IdRange>;
+
+    fn arbitrary_with(max: Option<u64>) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+
+        let max = max.unwrap_or(u64::MAX);
+        (0..max)
+            .prop_flat_map(move |b| (b..max).prop_map(move |e| IdRange { begin: b, end: e }))
+            .boxed()
+    }
+}
+
+struct IdRangeIter {
+    next: u64,
+    end: u64,
+    done: bool,
+}
+
+impl Iterator for IdRangeIter {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.done {
+            return None;
+        }
+        let id = self.next;
+        if id == self.end {
+            self.done = true;
+        } else {
+            self.next += 1;
+        }
+        Some(id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        //IdRange::iter only builds iterators whose length fits in usize
+        let count = (self.end - self.next) as usize + 1;
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for IdRangeIter {}
+
 fn run(path: &str) -> Result<(String, String)> {
     let now = Instant::now();
     let file = File::open(path)?;
@@ -54,47 +179,64 @@ fn part2(ranges: &[IdRange]) -> u64 {
         .sum()
 }
 
-fn compute_invalid(begin: u64, end: u64) -> Vec<u64> {
+fn compute_invalid(begin: u64, end: u64) -> impl Iterator<Item = u64> {
     let begin_digits_count = begin.to_string().len() as u32;
     let end_digits_count = end.to_string().len() as u32;
     let min_prefix_length = 1.max(begin_digits_count / 2);
     let max_prefix_length = 1.max(end_digits_count / 2);
-    (min_prefix_length..=max_prefix_length)
-        .flat_map(|length| {
-            let start_prefix = 10u64.pow(length - 1);
-            let limit = 10u64.pow(length);
+    (min_prefix_length..=max_prefix_length).flat_map(move |length| {
+        let start_prefix = 10u64.pow(length - 1);
+        let limit = 10u64.pow(length);
 
-            (start_prefix..limit)
-                .map(move |prefix| prefix * 10u64.pow(length) + prefix)
-                .skip_while(|id| *id < begin)
-                .take_while(|id| *id <= end)
-        })
-        .collect()
+        (start_prefix..limit)
+            .map(move |prefix| prefix * 10u64.pow(length) + prefix)
+            .skip_while(move |id| *id < begin)
+            .take_while(move |id| *id <= end)
+    })
 }
 
-fn compute_invalid2(begin: u64, end: u64) -> HashSet<u64> {
+fn compute_invalid2(begin: u64, end: u64) -> impl Iterator<Item = u64> {
     let begin_digits_count = begin.to_string().len() as u32;
     let end_digits_count = end.to_string().len() as u32;
     let max_prefix_length = 1.max(end_digits_count / 2);
-    (1..=max_prefix_length)
-        .flat_map(|length| {
-            let begin_repeat = (begin_digits_count / length).max(2);
-            let end_repeat = end_digits_count / length;
-            let start_prefix = 10u64.pow(length - 1);
-            let limit = 10u64.pow(length);
-
-            (begin_repeat..=end_repeat).flat_map(move |count_repeat| {
-                (start_prefix..limit)
-                    .map(move |prefix| repeat(prefix, count_repeat))
-                    .skip_while(move |id| *id < begin)
-                    .take_while(move |id| *id <= end)
-            })
+    (1..=max_prefix_length).flat_map(move |length| {
+        let begin_repeat = (begin_digits_count / length).max(2);
+        let end_repeat = end_digits_count / length;
+        let start_prefix = 10u64.pow(length - 1);
+        let limit = 10u64.pow(length);
+
+        //an id is only generated from its shortest prefix, so no duplicates
+        (begin_repeat..=end_repeat).flat_map(move |count_repeat| {
+            (start_prefix..limit)
+                .filter(|prefix| !is_repeat(*prefix))
+                .map(move |prefix| repeat(prefix, count_repeat))
+                .skip_while(move |id| *id < begin)
+                .take_while(move |id| *id <= end)
+        })
+    })
+}
+
+fn is_repeat(n: u64) -> bool {
+    repeat_components(n).is_some()
+}
+
+fn repeat_components(n: u64) -> Option<(u64, u32)> {
+    let length = digits_count(n);
+    (1..length)
+        .filter(|prefix_length| length.is_multiple_of(*prefix_length))
+        .find_map(|prefix_length| {
+            let prefix = n / 10u64.pow(length - prefix_length);
+            let count = length / prefix_length;
+            (repeat(prefix, count) == n).then_some((prefix, count))
         })
-        .collect()
+}
+
+fn digits_count(n: u64) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
 }
 
 fn repeat(prefix: u64, count: u32) -> u64 {
-    let length = prefix.to_string().len() as u32;
+    let length = digits_count(prefix);
     (0..count).map(|idx| prefix * 10u64.pow(idx * length)).sum()
 }
 
@@ -111,7 +253,10 @@ fn parse_range(bytes: &[u8]) -> Result<IdRange> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use proptest::proptest;
+    use proptest::{
+        arbitrary::{any, any_with},
+        proptest,
+    };
 
     #[test]
     fn test_part() {
@@ -122,16 +267,16 @@ mod tests {
 
     #[test]
     fn test_compute_invalid_1() {
-        let invalids = compute_invalid(11, 22);
+        let invalids: Vec<u64> = compute_invalid(11, 22).collect();
         assert_eq!(invalids, vec![11, 22]);
 
-        let invalids = compute_invalid(95, 115);
+        let invalids: Vec<u64> = compute_invalid(95, 115).collect();
         assert_eq!(invalids, vec![99]);
 
-        let invalids = compute_invalid(222220, 222224);
+        let invalids: Vec<u64> = compute_invalid(222220, 222224).collect();
         assert_eq!(invalids, vec![222222]);
 
-        let invalids = compute_invalid(1698522, 1698528);
+        let invalids: Vec<u64> = compute_invalid(1698522, 1698528).collect();
         assert_eq!(invalids, vec![]);
     }
 
@@ -142,7 +287,7 @@ mod tests {
         assert_eq!(repeat(1970, 3), 197019701970);
     }
 
-    fn is_repeat(chain: &str) -> bool {
+    fn is_repeat_str(chain: &str) -> bool {
         let chars: Vec<char> = chain.chars().collect();
         (1..chars.len())
             .filter(|chunk_count| chars.len().is_multiple_of(*chunk_count))
@@ -154,28 +299,207 @@ mod tests {
 
     #[test]
     fn test_is_repeat() {
-        assert!(is_repeat("111"));
-        assert!(is_repeat("1313"));
-        assert!(is_repeat("197019701970"));
-        assert!(!is_repeat("197019701971"));
+        assert!(is_repeat_str("111"));
+        assert!(is_repeat_str("1313"));
+        assert!(is_repeat_str("197019701970"));
+        assert!(!is_repeat_str("197019701971"));
     }
 
     #[test]
     fn test_low_invalid() {
-        assert_eq!(compute_invalid2(1, 14), HashSet::from([11]))
+        assert_eq!(compute_invalid2(1, 14).collect::<Vec<_>>(), vec![11])
     }
 
     proptest! {
         #[test]
-        fn test_invalids(a in 1..1000u64, b in 1..1000u64) {
-            let begin = a.min(b);
-            let end = a.max(b);
-            let invalids = compute_invalid2(begin, end);
+        fn test_invalids(range in any_with::<IdRange>(Some(1000))) {
+            let invalids = compute_invalid2(range.begin, range.end);
 
             for invalid in invalids {
-                assert!((begin..=end).contains(&invalid));
-                assert!(is_repeat(&invalid.to_string()));
+                assert!(range.contains(invalid));
+                assert!(is_repeat_str(&invalid.to_string()));
             }
         }
+
+        #[test]
+        fn test_invalids_unique(range in any_with::<IdRange>(Some(20_000))) {
+            let invalids: Vec<u64> = compute_invalid2(range.begin, range.end).collect();
+            let unique: HashSet<u64> = invalids.iter().copied().collect();
+            assert_eq!(unique.len(), invalids.len());
+
+            let expected: u64 = range.iter().expect("range too wide").filter(|id| is_repeat_str(&id.to_string())).sum();
+            assert_eq!(invalids.iter().sum::<u64>(), expected);
+        }
+
+        #[test]
+        fn test_id_range_round_trip(range in any::<IdRange>()) {
+            let parsed: IdRange = range.to_string().parse().unwrap();
+            assert_eq!(parsed, range);
+        }
+    }
+
+    #[test]
+    fn test_id_range() {
+        let range = IdRange { begin: 10, end: 15 };
+        assert_eq!(
+            range.iter().unwrap().collect::<Vec<_>>(),
+            vec![10, 11, 12, 13, 14, 15]
+        );
+        assert_eq!(range.iter().unwrap().len(), 6);
+        assert_eq!(range.len(), Some(6));
+        assert!(range.contains(10));
+        assert!(range.contains(15));
+        assert!(!range.contains(9));
+        assert!(!range.contains(16));
+
+        let single = IdRange { begin: 7, end: 7 };
+        assert_eq!(single.iter().unwrap().collect::<Vec<_>>(), vec![7]);
+        assert_eq!(single.len(), Some(1));
+
+        let top = IdRange {
+            begin: u64::MAX - 1,
+            end: u64::MAX,
+        };
+        assert_eq!(
+            top.iter().unwrap().collect::<Vec<_>>(),
+            vec![u64::MAX - 1, u64::MAX]
+        );
+        let full = IdRange {
+            begin: 0,
+            end: u64::MAX,
+        };
+        assert!(full.iter().is_none());
+        assert_eq!(full.len(), None);
+        let almost_full = IdRange {
+            begin: 1,
+            end: u64::MAX,
+        };
+        assert_eq!(almost_full.len(), Some(u64::MAX));
+        let mut almost_full = almost_full.iter().unwrap();
+        assert_eq!(almost_full.size_hint(), (usize::MAX, Some(usize::MAX)));
+        almost_full.next();
+        assert_eq!(almost_full.len(), usize::MAX - 1);
+
+        assert_eq!(
+            range.split_at(12),
+            (
+                Some(IdRange { begin: 10, end: 11 }),
+                Some(IdRange { begin: 13, end: 15 })
+            )
+        );
+        assert_eq!(
+            range.split_at(10),
+            (None, Some(IdRange { begin: 11, end: 15 }))
+        );
+        assert_eq!(
+            range.split_at(15),
+            (Some(IdRange { begin: 10, end: 14 }), None)
+        );
+        assert_eq!(single.split_at(7), (None, None));
+        assert_eq!(range.split_at(20), (Some(range), None));
+    }
+
+    #[test]
+    fn test_compute_invalid_lazy() {
+        assert_eq!(compute_invalid(11, 22).sum::<u64>(), 33);
+        assert_eq!(compute_invalid(95, 115).sum::<u64>(), 99);
+
+        let mut pulled = 0;
+        let first: Vec<u64> = compute_invalid(11, 999_999_999_999_999_999)
+            .inspect(|_| pulled += 1)
+            .take(3)
+            .collect();
+        assert_eq!(first, vec![11, 22, 33]);
+        assert_eq!(pulled, 3);
+    }
+
+    #[test]
+    fn test_id_range_parse_display() {
+        let range: IdRange = "11-22".parse().expect("could not parse");
+        assert_eq!(range, IdRange { begin: 11, end: 22 });
+        assert_eq!(range.to_string(), "11-22");
+        assert!("11:22".parse::<IdRange>().is_err());
+        assert!("11-x".parse::<IdRange>().is_err());
+
+        let mut ranges = vec![
+            IdRange { begin: 5, end: 9 },
+            IdRange { begin: 1, end: 20 },
+            IdRange { begin: 5, end: 6 },
+        ];
+        ranges.sort();
+        assert_eq!(
+            ranges,
+            vec![
+                IdRange { begin: 1, end: 20 },
+                IdRange { begin: 5, end: 6 },
+                IdRange { begin: 5, end: 9 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overlap_gap_union() {
+        let range = |begin, end| IdRange { begin, end };
+
+        assert_eq!(range(1, 5).overlap(&range(3, 9)), Some(range(3, 5)));
+        assert_eq!(range(3, 9).overlap(&range(1, 5)), Some(range(3, 5)));
+        assert_eq!(range(1, 10).overlap(&range(3, 4)), Some(range(3, 4)));
+        assert_eq!(range(1, 5).overlap(&range(5, 9)), Some(range(5, 5)));
+        assert_eq!(range(1, 3).overlap(&range(4, 6)), None);
+        assert_eq!(range(1, 3).overlap(&range(7, 9)), None);
+
+        assert_eq!(range(1, 3).gap_to(&range(7, 9)), Some(range(4, 6)));
+        assert_eq!(range(7, 9).gap_to(&range(1, 3)), Some(range(4, 6)));
+        assert_eq!(range(1, 3).gap_to(&range(5, 9)), Some(range(4, 4)));
+        assert_eq!(range(1, 3).gap_to(&range(4, 6)), None);
+        assert_eq!(range(1, 5).gap_to(&range(3, 9)), None);
+        assert_eq!(range(1, 10).gap_to(&range(3, 4)), None);
+        assert_eq!(range(0, 0).gap_to(&range(0, u64::MAX)), None);
+
+        assert_eq!(
+            range(1, 3).union_if_adjacent(&range(4, 6)),
+            Some(range(1, 6))
+        );
+        assert_eq!(
+            range(1, 5).union_if_adjacent(&range(3, 9)),
+            Some(range(1, 9))
+        );
+        assert_eq!(
+            range(3, 4).union_if_adjacent(&range(1, 10)),
+            Some(range(1, 10))
+        );
+        assert_eq!(range(1, 3).union_if_adjacent(&range(5, 9)), None);
+        assert_eq!(
+            range(5, u64::MAX).union_if_adjacent(&range(0, u64::MAX)),
+            Some(range(0, u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_repeat_components() {
+        assert_eq!(repeat_components(7), None);
+        assert_eq!(repeat_components(11), Some((1, 2)));
+        assert_eq!(repeat_components(1111), Some((1, 4)));
+        assert_eq!(repeat_components(1212), Some((12, 2)));
+        assert_eq!(repeat_components(123123123), Some((123, 3)));
+        assert_eq!(repeat_components(1213), None);
+        assert_eq!(repeat_components(1010), Some((10, 2)));
+        assert_eq!(repeat_components(100), None);
+        assert_eq!(repeat_components(0), None);
+        assert!(is_repeat(18446744071844674407));
+        assert!(!is_repeat(u64::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn test_is_repeat_matches_str(n in any::<u64>(), prefix in 1..100_000u64, count in 2..4u32) {
+            assert_eq!(is_repeat(n), is_repeat_str(&n.to_string()));
+
+            let id = repeat(prefix, count);
+            assert!(is_repeat(id));
+            let (smallest, smallest_count) = repeat_components(id).unwrap();
+            assert_eq!(repeat(smallest, smallest_count), id);
+            assert!(!is_repeat(smallest));
+        }
     }
 }
@@ -1,12 +1,107 @@
 use anyhow::{Result, anyhow};
 use std::{
+    collections::VecDeque,
     fs::File,
     io::{BufRead, BufReader},
     time::Instant,
 };
 
+#[derive(Debug, PartialEq)]
 struct BatteryLine(Vec<u8>);
 
+impl std::ops::Deref for BatteryLine {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for BatteryLine {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<BatteryLine> {
+        parse_line(s)
+    }
+}
+
+impl std::fmt::Display for BatteryLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for digit in self.iter() {
+            write!(f, "{digit}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BatteryLine {
+    fn digit_frequency(&self) -> [usize; 10] {
+        let mut frequency = [0; 10];
+        for digit in self.iter() {
+            frequency[*digit as usize] += 1;
+        }
+        frequency
+    }
+
+    #[allow(dead_code)]
+    fn entropy(&self) -> f64 {
+        let total = self.len() as f64;
+        self.digit_frequency()
+            .iter()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    //unlike the slice method, a size of 0 yields no window instead of panicking
+    #[allow(dead_code)]
+    fn windows(&self, size: usize) -> impl Iterator<Item = &[u8]> + '_ {
+        (size > 0)
+            .then(|| self.0.windows(size))
+            .into_iter()
+            .flatten()
+    }
+
+    #[cfg(test)]
+    fn voltage_loop(&self, size: usize) -> VoltageLoop<'_> {
+        VoltageLoop::with_digits(self, size, 10, u8::max)
+    }
+
+    #[allow(dead_code)]
+    fn reversed(&self) -> BatteryLine {
+        BatteryLine(self.iter().rev().copied().collect())
+    }
+
+    #[allow(dead_code)]
+    fn sliding_max(&self, window_size: usize) -> Vec<u8> {
+        if window_size == 0 || window_size > self.len() {
+            return vec![];
+        }
+        //indices of the current window, with decreasing values
+        let mut deque: VecDeque<usize> = VecDeque::new();
+        let mut maxes = Vec::with_capacity(self.len() - window_size + 1);
+        for (index, digit) in self.iter().enumerate() {
+            while deque.back().is_some_and(|&last| self[last] <= *digit) {
+                deque.pop_back();
+            }
+            deque.push_back(index);
+            if deque
+                .front()
+                .is_some_and(|&first| first + window_size <= index)
+            {
+                deque.pop_front();
+            }
+            if index + 1 >= window_size {
+                maxes.push(self[deque[0]]);
+            }
+        }
+        maxes
+    }
+}
+
 fn main() {
     let (part1, part2) = run("./files/input.txt").expect("could not run");
     println!("part1 : {part1}");
@@ -47,23 +142,36 @@ fn part2(battery_lines: &[BatteryLine]) -> u64 {
         .sum()
 }
 
-struct VoltageLoop {
+#[derive(Clone)]
+struct VoltageLoop<'a> {
     size: usize,
+    base: u8,
     values: Vec<Option<u8>>,
+    digits: &'a [u8],
+    index: usize,
+    cmp: fn(u8, u8) -> u8,
 }
 
-impl VoltageLoop {
-    fn new(size: usize) -> Self {
+impl<'a> VoltageLoop<'a> {
+    fn new(size: usize, base: u8) -> Self {
+        VoltageLoop::with_digits(&[], size, base, u8::max)
+    }
+
+    fn with_digits(digits: &'a [u8], size: usize, base: u8, cmp: fn(u8, u8) -> u8) -> Self {
         VoltageLoop {
             size,
+            base,
             values: vec![None; size],
+            digits,
+            index: 0,
+            cmp,
         }
     }
 
     fn update(&mut self, digit: u8, remaining_digits: usize) {
         let start_index = self.size.saturating_sub(remaining_digits);
         for index in start_index..self.size {
-            if self.values[index].is_none_or(|value| value < digit) {
+            if self.values[index].is_none_or(|value| (self.cmp)(value, digit) != value) {
                 self.values[index] = Some(digit);
                 for rem_index in index + 1..self.size {
                     self.values[rem_index] = None;
@@ -77,25 +185,58 @@ impl VoltageLoop {
         self.values
             .iter()
             .map(|v| v.expect("value should be filled") as u64)
-            .fold(0, |acc, val| acc * 10 + val)
+            .fold(0, |acc, val| acc * self.base as u64 + val)
+    }
+
+    fn peek_value(&self) -> Option<u64> {
+        self.is_ready().then(|| self.get_value())
+    }
+
+    fn remaining_capacity(&self) -> usize {
+        self.values.iter().filter(|value| value.is_none()).count()
+    }
+
+    fn is_ready(&self) -> bool {
+        self.remaining_capacity() == 0
+    }
+}
+
+impl Iterator for VoltageLoop<'_> {
+    type Item = Option<u64>;
+
+    fn next(&mut self) -> Option<Option<u64>> {
+        let digit = *self.digits.get(self.index)?;
+        self.update(digit, self.digits.len() - self.index);
+        self.index += 1;
+        Some(self.peek_value())
+    }
+}
+
+impl Default for VoltageLoop<'_> {
+    fn default() -> Self {
+        VoltageLoop::new(2, 10)
     }
 }
 
 fn compute_voltage(battery_line: &BatteryLine, size: usize) -> u64 {
-    let length = battery_line.0.len();
-    battery_line
-        .0
-        .iter()
-        .enumerate()
-        .fold(VoltageLoop::new(size), |mut acc, (index, digit)| {
-            acc.update(*digit, length - index);
-            acc
-        })
-        .get_value()
+    compute_voltage_with(battery_line, size, |a, b| a.max(b))
+}
+
+#[allow(dead_code)]
+fn compute_min_voltage(battery_line: &BatteryLine, size: usize) -> u64 {
+    compute_voltage_with(battery_line, size, |a, b| a.min(b))
+}
+
+fn compute_voltage_with(battery_line: &BatteryLine, size: usize, cmp: fn(u8, u8) -> u8) -> u64 {
+    VoltageLoop::with_digits(battery_line, size, 10, cmp)
+        .flatten()
+        .last()
+        .expect("line should have enough digits")
 }
 
 fn parse_line(line: &str) -> Result<BatteryLine> {
-    line.chars()
+    line.trim_end_matches(['\r', '\n'])
+        .chars()
         .map(|c| {
             c.to_digit(10)
                 .map(|digit| digit as u8)
@@ -115,4 +256,182 @@ mod tests {
         assert_eq!(&part1, "357");
         assert_eq!(&part2, "3121910778619");
     }
+
+    #[test]
+    fn test_voltage_loop_base() {
+        let line = parse_line("987654321111111").expect("could not parse");
+        assert_eq!(compute_voltage(&line, 2), 98);
+
+        let digits = [1, 15, 3, 10];
+        let mut voltage_loop = VoltageLoop::new(2, 16);
+        for (index, digit) in digits.iter().enumerate() {
+            voltage_loop.update(*digit, digits.len() - index);
+        }
+        assert_eq!(voltage_loop.get_value(), 0xfa);
+    }
+
+    #[test]
+    fn test_remaining_capacity() {
+        let mut voltage_loop = VoltageLoop::new(3, 10);
+        assert_eq!(voltage_loop.remaining_capacity(), 3);
+        assert!(!voltage_loop.is_ready());
+        voltage_loop.update(9, 3);
+        assert_eq!(voltage_loop.remaining_capacity(), 2);
+        voltage_loop.update(8, 2);
+        assert_eq!(voltage_loop.remaining_capacity(), 1);
+        voltage_loop.update(7, 1);
+        assert_eq!(voltage_loop.remaining_capacity(), 0);
+        assert!(voltage_loop.is_ready());
+    }
+
+    #[test]
+    fn test_sliding_max() {
+        let line = parse_line("818181911112111").expect("could not parse");
+        assert_eq!(
+            line.sliding_max(4),
+            vec![8, 8, 8, 9, 9, 9, 9, 1, 2, 2, 2, 2]
+        );
+        assert_eq!(line.sliding_max(1), line.to_vec());
+        assert_eq!(line.sliding_max(line.len()), vec![9]);
+        assert!(line.sliding_max(0).is_empty());
+        assert!(line.sliding_max(16).is_empty());
+
+        //the leading digit of the voltage is the best one leaving enough digits after it
+        let lines = [
+            "987654321111111",
+            "811111111111119",
+            "234234234234278",
+            "818181911112111",
+        ];
+        for line in lines.iter().map(|line| parse_line(line).unwrap()) {
+            for size in 1..=line.len() {
+                let leading = compute_voltage(&line, size) / 10u64.pow(size as u32 - 1);
+                assert_eq!(line.sliding_max(line.len() - size + 1)[0] as u64, leading);
+            }
+        }
+    }
+
+    #[test]
+    fn test_battery_line_impls() {
+        let line: BatteryLine = "818181911".parse().expect("could not parse");
+        assert_eq!(line.len(), 9);
+        assert_eq!(line[6], 9);
+        assert_eq!(line.iter().max(), Some(&9));
+        assert_eq!(line.to_string(), "818181911");
+        assert_eq!(line.reversed().to_string(), "119181818");
+        assert_eq!(line.reversed().reversed(), line);
+        assert!("12a4".parse::<BatteryLine>().is_err());
+    }
+
+    #[test]
+    fn test_voltage_loop_iterator() {
+        let line = parse_line("987654321111111").expect("could not parse");
+        let values: Vec<Option<u64>> = line.voltage_loop(3).collect();
+        assert_eq!(values.len(), line.len());
+        assert_eq!(values[..2], [None, None]);
+        assert_eq!(values[2..5], [Some(987), Some(987), Some(987)]);
+        assert_eq!(values.last(), Some(&Some(987)));
+
+        let line = parse_line("818181911112111").expect("could not parse");
+        assert_eq!(
+            line.voltage_loop(12).flatten().last(),
+            Some(compute_voltage(&line, 12))
+        );
+        assert_eq!(compute_voltage(&line, 12), 888911112111);
+    }
+
+    #[test]
+    fn test_compute_voltage_with() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let lines: Vec<BatteryLine> = input
+            .lines()
+            .map(|line| parse_line(line).expect("could not parse"))
+            .collect();
+        for line in lines.iter() {
+            for size in [2, 12] {
+                assert_eq!(
+                    compute_voltage_with(line, size, u8::max),
+                    compute_voltage(line, size)
+                );
+            }
+        }
+        assert_eq!(compute_min_voltage(&lines[2], 3), 222);
+        assert_eq!(compute_min_voltage(&lines[3], 2), 11);
+        assert_eq!(compute_min_voltage(&lines[0], 4), 1111);
+    }
+
+    #[test]
+    fn test_entropy() {
+        let line = parse_line("811111111111119").expect("could not parse");
+        assert_eq!(line.digit_frequency(), [0, 13, 0, 0, 0, 0, 0, 0, 1, 1]);
+        assert!((line.entropy() - 0.6998).abs() < 1e-4);
+
+        assert_eq!(BatteryLine(vec![5, 5, 5]).entropy(), 0.0);
+        let uniform = parse_line("0123456789").expect("could not parse");
+        assert!((uniform.entropy() - 10f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_voltage_loop_clone_default() {
+        let mut voltage_loop = VoltageLoop::default();
+        assert_eq!(voltage_loop.peek_value(), None);
+        assert_eq!(voltage_loop.remaining_capacity(), 2);
+
+        voltage_loop.update(8, 4);
+        assert_eq!(voltage_loop.peek_value(), None);
+        voltage_loop.update(1, 3);
+        assert_eq!(voltage_loop.peek_value(), Some(81));
+
+        let mut checkpoint = voltage_loop.clone();
+        voltage_loop.update(9, 2);
+        assert_eq!(voltage_loop.peek_value(), None);
+        voltage_loop.update(2, 1);
+        assert_eq!(voltage_loop.peek_value(), Some(92));
+
+        assert_eq!(checkpoint.peek_value(), Some(81));
+        checkpoint.update(5, 2);
+        checkpoint.update(4, 1);
+        assert_eq!(checkpoint.peek_value(), Some(85));
+        assert_eq!(voltage_loop.peek_value(), Some(92));
+    }
+
+    #[test]
+    fn test_windows() {
+        let line = parse_line("818181911112111").expect("could not parse");
+        for size in 1..=line.len() + 2 {
+            let expected = if size > line.len() {
+                0
+            } else {
+                line.len() - size + 1
+            };
+            assert_eq!(line.windows(size).count(), expected);
+            assert!(line.windows(size).all(|window| window.len() == size));
+        }
+        assert_eq!(line.windows(0).count(), 0);
+        assert_eq!(
+            line.windows(4).map(|window| window[3]).collect::<Vec<u8>>(),
+            line[3..].to_vec()
+        );
+
+        let entropies: Vec<f64> = line
+            .windows(3)
+            .map(|window| BatteryLine(window.to_vec()).entropy())
+            .collect();
+        assert_eq!(entropies.len(), 13);
+        assert!((entropies[0] - 0.9183).abs() < 1e-4);
+        assert_eq!(entropies[7], 0.0);
+
+        assert_eq!(BatteryLine(vec![7; 10]).entropy(), 0.0);
+        let uniform = parse_line("5820496137").expect("could not parse");
+        assert!((uniform.entropy() - 3.32).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_parse_line_endings() {
+        let expected = parse_line("12345").expect("could not parse");
+        for line in ["12345\r", "12345\r\n", "12345\n"] {
+            assert_eq!(parse_line(line).expect("could not parse"), expected);
+        }
+        assert!(parse_line("123\r45").is_err());
+    }
 }
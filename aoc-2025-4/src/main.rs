@@ -1,8 +1,7 @@
 use anyhow::{Result, anyhow};
 use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufRead, BufReader},
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
     time::Instant,
 };
 
@@ -12,10 +11,24 @@ struct Pos {
     y: isize,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Connectivity {
+    Four,
+    Eight,
+}
+
 impl Pos {
-    fn get_neighbors_pos(&self) -> Vec<Pos> {
+    fn get_neighbors_pos(&self, connectivity: Connectivity) -> Vec<Pos> {
         let x = self.x;
         let y = self.y;
+        if connectivity == Connectivity::Four {
+            return vec![
+                Pos { x, y: y - 1 },
+                Pos { x: x - 1, y },
+                Pos { x: x + 1, y },
+                Pos { x, y: y + 1 },
+            ];
+        }
         vec![
             Pos { x: x - 1, y: y - 1 },
             Pos { x, y: y - 1 },
@@ -29,25 +42,118 @@ impl Pos {
     }
 }
 
-#[derive(Clone)]
+impl std::ops::Add<Pos> for Pos {
+    type Output = Pos;
+
+    fn add(self, other: Pos) -> Pos {
+        Pos {
+            x: self.x.wrapping_add(other.x),
+            y: self.y.wrapping_add(other.y),
+        }
+    }
+}
+
+impl std::ops::Sub<Pos> for Pos {
+    type Output = Pos;
+
+    fn sub(self, other: Pos) -> Pos {
+        Pos {
+            x: self.x.wrapping_sub(other.x),
+            y: self.y.wrapping_sub(other.y),
+        }
+    }
+}
+
+impl std::fmt::Display for Pos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl std::str::FromStr for Pos {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Pos> {
+        let (x, y) = s
+            .trim()
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .and_then(|s| s.split_once(','))
+            .ok_or(anyhow!("could not parse position {s}"))?;
+        Ok(Pos {
+            x: x.trim().parse()?,
+            y: y.trim().parse()?,
+        })
+    }
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn with_capacity(size: usize) -> Self {
+        Self {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut j = i;
+        while self.parents[j] != j {
+            self.parents[j] = self.parents[self.parents[j]];
+            j = self.parents[j];
+        }
+        j
+    }
+
+    fn union(&mut self, x: usize, y: usize) {
+        let x = self.find(x);
+        let y = self.find(y);
+        self.parents[y] = x;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 struct Status {
     neighbors_count: u8,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct Grid {
     map: HashMap<Pos, Status>,
     marked_for_deletion: HashSet<Pos>,
+    connectivity: Connectivity,
+    threshold: u8,
 }
 
 impl Grid {
     fn new() -> Self {
+        Grid::new_with_threshold(4)
+    }
+
+    fn new_with_connectivity(connectivity: Connectivity) -> Self {
         Grid {
             map: HashMap::new(),
             marked_for_deletion: HashSet::new(),
+            connectivity,
+            threshold: 4,
+        }
+    }
+
+    //cells with fewer neighbors than the threshold get removed
+    fn new_with_threshold(threshold: u8) -> Self {
+        Grid {
+            threshold,
+            ..Grid::new_with_connectivity(Connectivity::Eight)
         }
     }
 
+    #[allow(dead_code)]
+    fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
     fn add(&mut self, pos: Pos) {
         let neighbors = self.get_neighbors(pos);
         for neighbor in neighbors.iter() {
@@ -55,7 +161,7 @@ impl Grid {
                 .entry(*neighbor)
                 .and_modify(|status| status.neighbors_count += 1);
 
-            if self.map.get(neighbor).unwrap().neighbors_count >= 4 {
+            if self.map.get(neighbor).unwrap().neighbors_count >= self.threshold {
                 self.marked_for_deletion.remove(neighbor);
             }
         }
@@ -66,13 +172,50 @@ impl Grid {
             },
         );
 
-        if neighbors.len() < 4 {
+        if (neighbors.len() as u8) < self.threshold {
             self.marked_for_deletion.insert(pos);
         }
     }
 
+    fn batch_add(&mut self, positions: impl IntoIterator<Item = Pos>) {
+        let mut added = HashSet::new();
+        let positions: Vec<Pos> = positions
+            .into_iter()
+            .filter(|pos| !self.map.contains_key(pos) && added.insert(*pos))
+            .collect();
+        for pos in positions.iter() {
+            self.map.insert(*pos, Status { neighbors_count: 0 });
+        }
+
+        let mut updated = HashSet::new();
+        for pos in positions.iter() {
+            let neighbors = self.get_neighbors(*pos);
+            for neighbor in neighbors
+                .iter()
+                .filter(|neighbor| !added.contains(neighbor))
+            {
+                if let Some(status) = self.map.get_mut(neighbor) {
+                    status.neighbors_count += 1;
+                }
+                updated.insert(*neighbor);
+            }
+            if let Some(status) = self.map.get_mut(pos) {
+                status.neighbors_count = neighbors.len() as u8;
+            }
+            if (neighbors.len() as u8) < self.threshold {
+                self.marked_for_deletion.insert(*pos);
+            }
+        }
+
+        for pos in updated {
+            if self.map[&pos].neighbors_count >= self.threshold {
+                self.marked_for_deletion.remove(&pos);
+            }
+        }
+    }
+
     fn get_neighbors(&self, pos: Pos) -> Vec<Pos> {
-        pos.get_neighbors_pos()
+        pos.get_neighbors_pos(self.connectivity)
             .into_iter()
             .filter(|neighbor_pos| self.map.contains_key(neighbor_pos))
             .collect()
@@ -87,6 +230,48 @@ impl Grid {
         self.marked_for_deletion = new_marked_for_deletion;
     }
 
+    fn remove_papers_with_history(&mut self) -> Vec<Pos> {
+        let removed = self.iter_marked().collect();
+        self.remove_papers_once();
+        removed
+    }
+
+    #[allow(dead_code)]
+    fn full_removal_history(&mut self) -> Vec<Vec<Pos>> {
+        let mut history = vec![];
+        while !self.marked_for_deletion.is_empty() {
+            history.push(self.remove_papers_with_history());
+        }
+        history
+    }
+
+    fn count_removal_rounds(&mut self) -> usize {
+        let mut rounds = 0;
+        while !self.marked_for_deletion.is_empty() {
+            self.remove_papers_once();
+            rounds += 1;
+        }
+        rounds
+    }
+
+    #[allow(dead_code)]
+    fn step_count_to_stable(&self) -> usize {
+        self.clone().count_removal_rounds()
+    }
+
+    #[allow(dead_code)]
+    fn simulate_n_rounds(&self, n: usize) -> (Grid, usize) {
+        let mut grid = self.clone();
+        for _ in 0..n {
+            if grid.marked_for_deletion.is_empty() {
+                break;
+            }
+            grid.remove_papers_once();
+        }
+        let removed = self.size() - grid.size();
+        (grid, removed)
+    }
+
     fn remove(&mut self, pos: Pos) -> Vec<Pos> {
         let neighbors = self.get_neighbors(pos);
         let mut marked_for_deletion = vec![];
@@ -94,7 +279,7 @@ impl Grid {
             self.map.entry(*neighbor).and_modify(|status| {
                 status.neighbors_count = status.neighbors_count.saturating_sub(1);
             });
-            if self.map.get(neighbor).unwrap().neighbors_count < 4 {
+            if self.map.get(neighbor).unwrap().neighbors_count < self.threshold {
                 marked_for_deletion.push(*neighbor);
             }
         }
@@ -102,9 +287,173 @@ impl Grid {
         marked_for_deletion
     }
 
+    fn connected_components(&self) -> Vec<HashSet<Pos>> {
+        let positions: Vec<Pos> = self.map.keys().copied().collect();
+        let indexes: HashMap<Pos, usize> = positions
+            .iter()
+            .enumerate()
+            .map(|(index, pos)| (*pos, index))
+            .collect();
+
+        let mut union_find = UnionFind::with_capacity(positions.len());
+        for (index, pos) in positions.iter().enumerate() {
+            for neighbor in self.get_neighbors(*pos) {
+                union_find.union(index, indexes[&neighbor]);
+            }
+        }
+
+        let mut components: HashMap<usize, HashSet<Pos>> = HashMap::new();
+        for (index, pos) in positions.iter().enumerate() {
+            components
+                .entry(union_find.find(index))
+                .or_default()
+                .insert(*pos);
+        }
+        let mut components: Vec<HashSet<Pos>> = components.into_values().collect();
+        components.sort_by_key(|component| std::cmp::Reverse(component.len()));
+        components
+    }
+
+    //an empty grid counts as fully connected
+    #[allow(dead_code)]
+    fn is_fully_connected(&self) -> bool {
+        self.connected_components().len() <= 1
+    }
+
+    #[allow(dead_code)]
+    fn distance(&self, pos1: Pos, pos2: Pos) -> Option<usize> {
+        if !self.map.contains_key(&pos1) || !self.map.contains_key(&pos2) {
+            return None;
+        }
+
+        let mut visited = HashSet::from([pos1]);
+        let mut queue = VecDeque::from([(pos1, 0)]);
+        while let Some((pos, distance)) = queue.pop_front() {
+            if pos == pos2 {
+                return Some(distance);
+            }
+            for neighbor in self.get_neighbors(pos) {
+                if visited.insert(neighbor) {
+                    queue.push_back((neighbor, distance + 1));
+                }
+            }
+        }
+        None
+    }
+
+    #[allow(dead_code)]
+    fn flood_fill(&self, start: Pos) -> HashSet<Pos> {
+        if !self.map.contains_key(&start) {
+            return HashSet::new();
+        }
+
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pos) = queue.pop_front() {
+            for neighbor in self.get_neighbors(pos) {
+                if visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        visited
+    }
+
+    fn empty_like(&self) -> Grid {
+        Grid {
+            threshold: self.threshold,
+            ..Grid::new_with_connectivity(self.connectivity)
+        }
+    }
+
+    //both keep the connectivity and threshold of self and recompute every neighbors count
+    #[allow(dead_code)]
+    fn union(&self, other: &Grid) -> Grid {
+        let mut grid = self.empty_like();
+        grid.batch_add(self.iter_cells().chain(other.iter_cells()));
+        grid
+    }
+
+    #[allow(dead_code)]
+    fn intersection(&self, other: &Grid) -> Grid {
+        let mut grid = self.empty_like();
+        grid.batch_add(
+            self.iter_cells()
+                .filter(|pos| other.neighbors_count_of(*pos).is_some()),
+        );
+        grid
+    }
+
+    #[allow(dead_code)]
+    fn bounding_box(&self) -> Option<(Pos, Pos)> {
+        let min_x = self.map.keys().map(|pos| pos.x).min()?;
+        let max_x = self.map.keys().map(|pos| pos.x).max()?;
+        let min_y = self.map.keys().map(|pos| pos.y).min()?;
+        let max_y = self.map.keys().map(|pos| pos.y).max()?;
+        Some((Pos { x: min_x, y: min_y }, Pos { x: max_x, y: max_y }))
+    }
+
+    //x is the line and y the column, as in the input
+    #[allow(dead_code)]
+    fn to_ascii(&self, min_x: isize, max_x: isize, min_y: isize, max_y: isize) -> String {
+        (min_x..=max_x)
+            .map(|x| {
+                (min_y..=max_y)
+                    .map(|y| {
+                        if self.map.contains_key(&Pos { x, y }) {
+                            '@'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     fn size(&self) -> usize {
         self.map.len()
     }
+
+    fn iter_cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.map.keys().copied()
+    }
+
+    fn iter_marked(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.marked_for_deletion.iter().copied()
+    }
+
+    fn neighbors_count_of(&self, pos: Pos) -> Option<u8> {
+        self.map.get(&pos).map(|status| status.neighbors_count)
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
+impl std::str::FromStr for Grid {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Grid> {
+        let mut grid = Grid::new();
+        for (x, line) in s.lines().enumerate() {
+            for (y, ch) in line.chars().enumerate() {
+                match ch {
+                    '@' => grid.add(Pos {
+                        x: x as isize,
+                        y: y as isize,
+                    }),
+                    '.' => {}
+                    _ => return Err(anyhow!("unexpected character {ch:?} at line {x}")),
+                }
+            }
+        }
+        Ok(grid)
+    }
 }
 
 fn main() {
@@ -115,22 +464,8 @@ fn main() {
 
 fn run(path: &str) -> Result<(String, String)> {
     let now = Instant::now();
-    let file = File::open(path)?;
-    let mut grid = Grid::new();
-    let lines = BufReader::new(file)
-        .lines()
-        .map(|line| line.map_err(|_| anyhow!("could not read line")))
-        .collect::<Result<Vec<String>>>()?;
-    for (x, line) in lines.iter().enumerate() {
-        for (y, ch) in line.chars().enumerate() {
-            if ch == '@' {
-                grid.add(Pos {
-                    x: x as isize,
-                    y: y as isize,
-                });
-            }
-        }
-    }
+    let content = fs::read_to_string(path)?;
+    let grid = parse_str(&content)?;
     println!("duration parsing : {:?}", now.elapsed());
 
     let now = Instant::now();
@@ -144,6 +479,10 @@ fn run(path: &str) -> Result<(String, String)> {
     Ok((part1.to_string(), part2.to_string()))
 }
 
+fn parse_str(content: &str) -> Result<Grid> {
+    content.parse()
+}
+
 fn part1(grid: &Grid) -> usize {
     let mut grid = grid.clone();
     let init_size = grid.size();
@@ -154,12 +493,7 @@ fn part1(grid: &Grid) -> usize {
 fn part2(grid: &Grid) -> usize {
     let mut grid = grid.clone();
     let init_size = grid.size();
-    loop {
-        grid.remove_papers_once();
-        if grid.marked_for_deletion.is_empty() {
-            break;
-        }
-    }
+    grid.count_removal_rounds();
     init_size - grid.size()
 }
 
@@ -167,10 +501,382 @@ fn part2(grid: &Grid) -> usize {
 mod tests {
     use super::*;
 
+    fn grid_from_str(input: &str, connectivity: Connectivity) -> Grid {
+        let mut grid = Grid::new_with_connectivity(connectivity);
+        for (x, line) in input.lines().enumerate() {
+            for (y, ch) in line.chars().enumerate() {
+                if ch == '@' {
+                    grid.add(Pos {
+                        x: x as isize,
+                        y: y as isize,
+                    });
+                }
+            }
+        }
+        grid
+    }
+
     #[test]
     fn test_part() {
         let (part1, part2) = run("./files/test.txt").expect("could not run");
         assert_eq!(&part1, "13");
         assert_eq!(&part2, "43");
     }
+
+    #[test]
+    fn test_connectivity() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+        assert_eq!(part1(&grid), 13);
+        assert_eq!(part2(&grid), 43);
+
+        let block = "@@@\n@@@\n@@@\n";
+        let grid = grid_from_str(block, Connectivity::Eight);
+        assert_eq!(part1(&grid), 4);
+        assert_eq!(part2(&grid), 9);
+
+        let grid = grid_from_str(block, Connectivity::Four);
+        assert_eq!(part1(&grid), 8);
+        assert_eq!(part2(&grid), 9);
+    }
+
+    #[test]
+    fn test_removal_rounds() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+        assert_eq!(grid.clone().count_removal_rounds(), 9);
+
+        assert_eq!(grid.simulate_n_rounds(0).1, 0);
+        assert_eq!(grid.simulate_n_rounds(1).1, part1(&grid));
+        let (stable, removed) = grid.simulate_n_rounds(usize::MAX);
+        assert_eq!(removed, part2(&grid));
+        assert!(stable.marked_for_deletion.is_empty());
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let mut grid = Grid::new();
+        for (x, y) in [(1, 2), (1, 3), (2, 1), (3, 3)] {
+            grid.add(Pos { x, y });
+        }
+        let (min, max) = grid.bounding_box().expect("grid is not empty");
+        assert_eq!(min, Pos { x: 1, y: 1 });
+        assert_eq!(max, Pos { x: 3, y: 3 });
+        assert_eq!(grid.to_ascii(min.x, max.x, min.y, max.y), ".@@\n@..\n..@");
+        assert_eq!(grid.to_ascii(0, 1, 0, 4), ".....\n..@@.");
+        assert_eq!(Grid::new().bounding_box(), None);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+        let sizes: Vec<usize> = grid
+            .connected_components()
+            .iter()
+            .map(|component| component.len())
+            .collect();
+        assert_eq!(sizes, vec![71]);
+
+        let (stable, _) = grid.simulate_n_rounds(usize::MAX);
+        let sizes: Vec<usize> = stable
+            .connected_components()
+            .iter()
+            .map(|component| component.len())
+            .collect();
+        assert_eq!(sizes, vec![28]);
+
+        let grid = grid_from_str(&input, Connectivity::Four);
+        let components = grid.connected_components();
+        let sizes: Vec<usize> = components.iter().map(|component| component.len()).collect();
+        assert_eq!(sizes, vec![69, 1, 1]);
+        assert_eq!(sizes.iter().sum::<usize>(), grid.size());
+
+        assert!(Grid::new().connected_components().is_empty());
+    }
+
+    #[test]
+    fn test_distance() {
+        let grid = grid_from_str("@@@@@.@\n......@\n", Connectivity::Four);
+        let start = Pos { x: 0, y: 0 };
+        assert_eq!(grid.distance(start, start), Some(0));
+        assert_eq!(grid.distance(start, Pos { x: 0, y: 4 }), Some(4));
+        assert_eq!(grid.distance(Pos { x: 0, y: 4 }, start), Some(4));
+        assert_eq!(grid.distance(start, Pos { x: 1, y: 6 }), None);
+        assert_eq!(grid.distance(start, Pos { x: 1, y: 0 }), None);
+        assert_eq!(
+            grid.distance(Pos { x: 0, y: 6 }, Pos { x: 1, y: 6 }),
+            Some(1)
+        );
+
+        let grid = grid_from_str("@@\n.@@\n", Connectivity::Eight);
+        assert_eq!(
+            grid.distance(Pos { x: 0, y: 0 }, Pos { x: 1, y: 2 }),
+            Some(2)
+        );
+        let grid = grid_from_str("@@\n.@@\n", Connectivity::Four);
+        assert_eq!(
+            grid.distance(Pos { x: 0, y: 0 }, Pos { x: 1, y: 2 }),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_batch_add() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+
+        let positions: Vec<Pos> = input
+            .lines()
+            .enumerate()
+            .flat_map(|(x, line)| {
+                line.char_indices()
+                    .filter(|(_, ch)| *ch == '@')
+                    .map(move |(y, _)| Pos {
+                        x: x as isize,
+                        y: y as isize,
+                    })
+            })
+            .collect();
+        let mut batch = Grid::new();
+        batch.batch_add(positions.iter().copied());
+        assert_eq!(batch.map, grid.map);
+        assert_eq!(batch.marked_for_deletion, grid.marked_for_deletion);
+
+        let (mut individual, _) = grid.simulate_n_rounds(2);
+        let (mut batch, _) = grid.simulate_n_rounds(2);
+        let extra: Vec<Pos> = positions
+            .iter()
+            .filter(|pos| !individual.map.contains_key(pos))
+            .take(5)
+            .chain([Pos { x: 0, y: 0 }].iter())
+            .copied()
+            .collect();
+        for pos in extra.iter() {
+            individual.add(*pos);
+        }
+        batch.batch_add(extra);
+        assert_eq!(batch.map, individual.map);
+        assert_eq!(batch.marked_for_deletion, individual.marked_for_deletion);
+    }
+
+    #[test]
+    fn test_removal_history() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+
+        let mut first_round = grid.clone();
+        let removed = first_round.remove_papers_with_history();
+        assert_eq!(removed.len(), part1(&grid));
+        assert!(removed.iter().all(|pos| !first_round.map.contains_key(pos)));
+
+        let mut history_grid = grid.clone();
+        let history = history_grid.full_removal_history();
+        assert_eq!(history.len(), 9);
+        assert_eq!(
+            history.iter().map(|round| round.len()).sum::<usize>(),
+            part2(&grid)
+        );
+
+        let mut replay = grid.clone();
+        for round in history.iter() {
+            assert!(round.iter().all(|pos| replay.map.contains_key(pos)));
+            let removed: HashSet<Pos> = replay.remove_papers_with_history().into_iter().collect();
+            assert_eq!(removed, round.iter().copied().collect());
+        }
+    }
+
+    #[test]
+    fn test_pos_operators() {
+        assert_eq!(Pos { x: 1, y: 2 } + Pos { x: -1, y: 3 }, Pos { x: 0, y: 5 });
+        assert_eq!(
+            Pos { x: 1, y: 2 } - Pos { x: -1, y: 3 },
+            Pos { x: 2, y: -1 }
+        );
+        assert_eq!(
+            Pos {
+                x: isize::MAX,
+                y: 0
+            } + Pos { x: 1, y: 0 },
+            Pos {
+                x: isize::MIN,
+                y: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_pos_display_from_str() {
+        let pos = Pos { x: -4, y: 12 };
+        assert_eq!(pos.to_string(), "(-4, 12)");
+        assert_eq!(pos.to_string().parse::<Pos>().unwrap(), pos);
+        assert_eq!("(3,7)".parse::<Pos>().unwrap(), Pos { x: 3, y: 7 });
+        assert!("3, 7".parse::<Pos>().is_err());
+        assert!("(a, 7)".parse::<Pos>().is_err());
+    }
+
+    #[test]
+    fn test_grid_from_str() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = parse_str(&input).expect("could not parse");
+        assert_eq!(grid, grid_from_str(&input, Connectivity::Eight));
+        assert_eq!(part1(&grid), 13);
+        assert_eq!(part2(&grid), 43);
+
+        assert_eq!("".parse::<Grid>().unwrap(), Grid::default());
+        assert!("@.\n.#".parse::<Grid>().is_err());
+
+        let crlf = input.replace('\n', "\r\n");
+        assert_eq!(parse_str(&crlf).expect("could not parse"), grid);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        for connectivity in [Connectivity::Eight, Connectivity::Four] {
+            let grid = grid_from_str(&input, connectivity);
+            for component in grid.connected_components() {
+                for pos in component.iter() {
+                    assert_eq!(grid.flood_fill(*pos), component);
+                }
+            }
+        }
+
+        let grid = grid_from_str("@@.\n..@\n", Connectivity::Four);
+        assert_eq!(
+            grid.flood_fill(Pos { x: 0, y: 1 }),
+            HashSet::from([Pos { x: 0, y: 0 }, Pos { x: 0, y: 1 }])
+        );
+        assert_eq!(
+            grid.flood_fill(Pos { x: 1, y: 2 }),
+            HashSet::from([Pos { x: 1, y: 2 }])
+        );
+        assert!(grid.flood_fill(Pos { x: 0, y: 2 }).is_empty());
+    }
+
+    #[test]
+    fn test_union_intersection() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+        assert_eq!(grid.union(&grid), grid);
+        assert_eq!(grid.intersection(&grid), grid);
+        assert_eq!(grid.union(&Grid::new()), grid);
+        assert_eq!(grid.intersection(&Grid::new()), Grid::new());
+
+        let (stable, _) = grid.simulate_n_rounds(usize::MAX);
+        let shifted = grid_from_str(&format!("\n{input}"), Connectivity::Eight);
+        for other in [&stable, &shifted] {
+            let intersection = grid.intersection(other);
+            let union = grid.union(other);
+            assert!(
+                intersection
+                    .map
+                    .keys()
+                    .all(|pos| grid.map.contains_key(pos))
+            );
+            assert!(
+                intersection
+                    .map
+                    .keys()
+                    .all(|pos| other.map.contains_key(pos))
+            );
+            assert!(grid.map.keys().all(|pos| union.map.contains_key(pos)));
+            assert!(other.map.keys().all(|pos| union.map.contains_key(pos)));
+            assert_eq!(
+                union.size() + intersection.size(),
+                grid.size() + other.size()
+            );
+        }
+
+        let stable_intersection = grid.intersection(&stable);
+        assert_eq!(stable_intersection.map, stable.map);
+        assert_eq!(grid.union(&stable), grid);
+    }
+
+    #[test]
+    fn test_fully_connected_and_stable() {
+        assert!(Grid::new().is_fully_connected());
+        assert_eq!(Grid::new().step_count_to_stable(), 0);
+
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+        assert!(grid.is_fully_connected());
+        assert!(!grid_from_str(&input, Connectivity::Four).is_fully_connected());
+
+        let before = grid.clone();
+        assert_eq!(grid.step_count_to_stable(), 9);
+        assert_eq!(grid, before);
+        assert_eq!(
+            grid.step_count_to_stable(),
+            grid.clone().count_removal_rounds()
+        );
+
+        let (stable, _) = grid.simulate_n_rounds(usize::MAX);
+        assert_eq!(stable.step_count_to_stable(), 0);
+    }
+
+    #[test]
+    fn test_iter_cells() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = grid_from_str(&input, Connectivity::Eight);
+        assert_eq!(grid.iter_cells().count(), grid.size());
+        assert_eq!(grid.iter_marked().count(), grid.marked_for_deletion.len());
+        assert_eq!(grid.iter_marked().count(), part1(&grid));
+        assert!(
+            grid.iter_marked()
+                .all(|pos| grid.neighbors_count_of(pos) < Some(4))
+        );
+
+        assert_eq!(grid.neighbors_count_of(Pos { x: 0, y: 0 }), None);
+        assert_eq!(grid.neighbors_count_of(Pos { x: 0, y: 2 }), Some(3));
+        assert_eq!(grid.neighbors_count_of(Pos { x: 1, y: 1 }), Some(6));
+        assert_eq!(grid.neighbors_count_of(Pos { x: -1, y: 0 }), None);
+
+        let grid = grid_from_str("@@\n.@\n", Connectivity::Four);
+        let mut cells: Vec<Pos> = grid.iter_cells().collect();
+        cells.sort_by_key(|pos| (pos.x, pos.y));
+        assert_eq!(
+            cells,
+            vec![Pos { x: 0, y: 0 }, Pos { x: 0, y: 1 }, Pos { x: 1, y: 1 }]
+        );
+        assert_eq!(grid.neighbors_count_of(Pos { x: 0, y: 1 }), Some(2));
+        assert_eq!(grid.neighbors_count_of(Pos { x: 1, y: 1 }), Some(1));
+        assert_eq!(grid.iter_marked().count(), 3);
+    }
+
+    #[test]
+    fn test_threshold() {
+        let input = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let grid = parse_str(&input).expect("could not parse");
+        assert_eq!(Grid::new().threshold(), 4);
+        assert_eq!(grid.threshold(), 4);
+        assert_eq!(grid, grid_from_str(&input, Connectivity::Eight));
+        assert_eq!(part1(&grid), 13);
+        assert_eq!(part2(&grid), 43);
+
+        let with_threshold = |threshold: u8| {
+            let mut other = Grid::new_with_threshold(threshold);
+            other.batch_add(grid.iter_cells());
+            other
+        };
+        assert_eq!(with_threshold(4), grid);
+
+        //no cell of the example is isolated
+        let loose = with_threshold(1);
+        assert_eq!(loose.threshold(), 1);
+        assert_eq!(part1(&loose), 0);
+        assert_eq!(part2(&loose), 0);
+
+        let strict = with_threshold(8);
+        assert!(part1(&strict) > part1(&grid));
+        assert!(part2(&strict) > part2(&grid));
+        assert_eq!(part2(&strict), strict.size());
+        assert_eq!(strict.union(&strict).threshold(), 8);
+
+        let mut isolated = Grid::new_with_threshold(1);
+        isolated.add(Pos { x: 0, y: 0 });
+        isolated.add(Pos { x: 5, y: 5 });
+        isolated.add(Pos { x: 5, y: 6 });
+        assert_eq!(part1(&isolated), 1);
+    }
 }
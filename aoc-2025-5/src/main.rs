@@ -1,4 +1,7 @@
-use std::time::Instant;
+use std::{
+    sync::{Arc, RwLock},
+    time::Instant,
+};
 
 use anyhow::{Result, anyhow};
 
@@ -14,7 +17,8 @@ enum RangeContainResult {
     Inside,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct FoodRange {
     lower: u64,
     upper: u64,
@@ -75,9 +79,33 @@ impl FoodRange {
     fn size(&self) -> u64 {
         self.upper - self.lower + 1
     }
+
+    #[allow(dead_code)]
+    fn shift(&self, offset: i64) -> Option<FoodRange> {
+        Some(FoodRange {
+            lower: self.lower.checked_add_signed(offset)?,
+            upper: self.upper.checked_add_signed(offset)?,
+        })
+    }
+
+    #[allow(dead_code)]
+    fn shift_saturating(&self, offset: i64) -> FoodRange {
+        FoodRange {
+            lower: self.lower.saturating_add_signed(offset),
+            upper: self.upper.saturating_add_signed(offset),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn scale(&self, factor: u64) -> Option<FoodRange> {
+        Some(FoodRange {
+            lower: self.lower.checked_mul(factor)?,
+            upper: self.upper.checked_mul(factor)?,
+        })
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct NodeRange {
     value: FoodRange,
     left: RangeTree,
@@ -106,8 +134,13 @@ impl NodeRange {
     }
 
     fn push_new_lower(&mut self, new_lower: u64) -> bool {
+        //only the right side can reach the new lower
+        if self.value.upper < new_lower {
+            self.right.push_new_lower(new_lower);
+            return true;
+        }
         self.left.push_new_lower(new_lower);
-        self.right.push_new_lower(new_lower);
+        self.right = RangeTree::new();
         match self.value.adapt_new_lower(new_lower) {
             Some(adapted) => {
                 self.value = adapted;
@@ -118,7 +151,12 @@ impl NodeRange {
     }
 
     fn push_new_upper(&mut self, new_upper: u64) -> bool {
-        self.left.push_new_upper(new_upper);
+        //only the left side can reach the new upper
+        if self.value.lower > new_upper {
+            self.left.push_new_upper(new_upper);
+            return true;
+        }
+        self.left = RangeTree::new();
         self.right.push_new_upper(new_upper);
         match self.value.adapt_new_upper(new_upper) {
             Some(adapted) => {
@@ -140,11 +178,18 @@ impl NodeRange {
     fn size(&self) -> u64 {
         self.value.size() + self.left.size() + self.right.size()
     }
+
+    fn collect_sorted(&self, ranges: &mut Vec<FoodRange>) {
+        self.left.collect_sorted(ranges);
+        ranges.push(self.value);
+        self.right.collect_sorted(ranges);
+    }
 }
 
-#[derive(Debug)]
+//nodes are shared between trees, and only copied when modified
+#[derive(Debug, Clone)]
 struct RangeTree {
-    node: Option<Box<NodeRange>>,
+    node: Option<Arc<NodeRange>>,
 }
 
 impl RangeTree {
@@ -154,11 +199,54 @@ impl RangeTree {
 
     fn insert(&mut self, range: FoodRange) {
         match &mut self.node {
-            Some(node) => node.insert(range),
-            None => self.node = Some(Box::new(NodeRange::new(range))),
+            Some(node) => Arc::make_mut(node).insert(range),
+            None => self.node = Some(Arc::new(NodeRange::new(range))),
+        }
+    }
+
+    fn from_sorted_ranges(ranges: &[FoodRange]) -> RangeTree {
+        if ranges.is_empty() {
+            return RangeTree::new();
+        }
+        let middle = ranges.len() / 2;
+        RangeTree {
+            node: Some(Arc::new(NodeRange {
+                value: ranges[middle],
+                left: RangeTree::from_sorted_ranges(&ranges[..middle]),
+                right: RangeTree::from_sorted_ranges(&ranges[middle + 1..]),
+            })),
         }
     }
 
+    #[allow(dead_code)]
+    fn insert_persistent(&self, range: FoodRange) -> RangeTree {
+        let mut tree = self.clone();
+        tree.insert(range);
+        tree
+    }
+
+    fn to_sorted_vec(&self) -> Vec<FoodRange> {
+        let mut ranges = vec![];
+        self.collect_sorted(&mut ranges);
+        ranges
+    }
+
+    fn collect_sorted(&self, ranges: &mut Vec<FoodRange>) {
+        if let Some(node) = &self.node {
+            node.collect_sorted(ranges);
+        }
+    }
+
+    #[allow(dead_code)]
+    fn symmetric_difference(&self, other: &RangeTree) -> RangeTree {
+        let ranges = self.to_sorted_vec();
+        let other_ranges = other.to_sorted_vec();
+        let mut difference = subtract_sorted_ranges(&ranges, &other_ranges);
+        difference.extend(subtract_sorted_ranges(&other_ranges, &ranges));
+        difference.sort_by_key(|range| range.lower);
+        RangeTree::from_sorted_ranges(&difference)
+    }
+
     fn contains(&self, id: u64) -> bool {
         self.node.as_ref().is_some_and(|node| node.contains(id))
     }
@@ -169,6 +257,7 @@ impl RangeTree {
 
     fn push_new_lower(&mut self, new_lower: u64) {
         if let Some(node) = &mut self.node {
+            let node = Arc::make_mut(node);
             let is_node_alive = node.push_new_lower(new_lower);
             if !is_node_alive {
                 self.node = node.left.node.take();
@@ -178,6 +267,7 @@ impl RangeTree {
 
     fn push_new_upper(&mut self, new_upper: u64) {
         if let Some(node) = &mut self.node {
+            let node = Arc::make_mut(node);
             let is_node_alive = node.push_new_upper(new_upper);
             if !is_node_alive {
                 self.node = node.right.node.take();
@@ -186,6 +276,74 @@ impl RangeTree {
     }
 }
 
+impl PartialEq for RangeTree {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_sorted_vec() == other.to_sorted_vec()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RangeTree {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_sorted_vec().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RangeTree {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ranges = Vec::<FoodRange>::deserialize(deserializer)?;
+        let is_sorted = ranges.iter().all(|range| range.lower <= range.upper)
+            && ranges.windows(2).all(|pair| pair[0].upper < pair[1].lower);
+        if !is_sorted {
+            return Err(serde::de::Error::custom(
+                "ranges should be sorted and disjoint",
+            ));
+        }
+        Ok(RangeTree::from_sorted_ranges(&ranges))
+    }
+}
+
+impl IntoIterator for &RangeTree {
+    type Item = FoodRange;
+    type IntoIter = std::vec::IntoIter<FoodRange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.to_sorted_vec().into_iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SharedRangeTree(Arc<RwLock<RangeTree>>);
+
+impl SharedRangeTree {
+    #[allow(dead_code)]
+    fn new() -> Self {
+        SharedRangeTree(Arc::new(RwLock::new(RangeTree::new())))
+    }
+
+    #[allow(dead_code)]
+    fn insert(&self, range: FoodRange) {
+        self.0
+            .write()
+            .expect("range tree lock poisoned")
+            .insert(range);
+    }
+
+    #[allow(dead_code)]
+    fn contains(&self, id: u64) -> bool {
+        self.0
+            .read()
+            .expect("range tree lock poisoned")
+            .contains(id)
+    }
+
+    #[allow(dead_code)]
+    fn size(&self) -> u64 {
+        self.0.read().expect("range tree lock poisoned").size()
+    }
+}
+
 fn main() {
     let (part1, part2) = run("./files/input.txt").expect("could not run");
     println!("part1 : {part1}");
@@ -216,6 +374,36 @@ fn part2(tree: &RangeTree) -> u64 {
     tree.size()
 }
 
+//both inputs must be sorted and disjoint, output is too
+fn subtract_sorted_ranges(ranges: &[FoodRange], removed: &[FoodRange]) -> Vec<FoodRange> {
+    let mut result = Vec::new();
+    let mut first_removed = 0;
+    for range in ranges {
+        while first_removed < removed.len() && removed[first_removed].upper < range.lower {
+            first_removed += 1;
+        }
+        let mut lower = Some(range.lower);
+        for removed_range in removed[first_removed..]
+            .iter()
+            .take_while(|removed_range| removed_range.lower <= range.upper)
+        {
+            let current = lower.expect("lower should be set while overlapping");
+            if removed_range.lower > current {
+                result.push(FoodRange::new(current, removed_range.lower - 1));
+            }
+            if removed_range.upper >= range.upper {
+                lower = None;
+                break;
+            }
+            lower = Some(removed_range.upper + 1);
+        }
+        if let Some(lower) = lower {
+            result.push(FoodRange::new(lower, range.upper));
+        }
+    }
+    result
+}
+
 fn parse_file(path: &str) -> Result<(RangeTree, Vec<u64>)> {
     let content = std::fs::read_to_string(path)?;
     let (ranges, ids_str) = content
@@ -421,4 +609,157 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_shared_range_tree() {
+        let shared = SharedRangeTree::new();
+        let handles: Vec<_> = (0..4u64)
+            .map(|thread| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for index in 0..1000u64 {
+                        //spread the insertion order to keep the tree shallow
+                        let slot = thread * 1000 + (index * 617) % 1000;
+                        shared.insert(FoodRange::new(slot * 10, slot * 10 + 4));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("thread panicked");
+        }
+
+        assert_eq!(shared.size(), 4 * 1000 * 5);
+        assert!(shared.contains(0));
+        assert!(shared.contains(39994));
+        assert!(!shared.contains(39995));
+        assert!(!shared.contains(15));
+    }
+
+    #[test]
+    fn test_insert_persistent() {
+        let mut tree = RangeTree::new();
+        for (lower, upper) in [(10, 14), (3, 5), (20, 25), (40, 41), (16, 18)] {
+            tree.insert(FoodRange::new(lower, upper));
+        }
+        let before = tree.to_sorted_vec();
+
+        let fused = tree.insert_persistent(FoodRange::new(12, 21));
+        assert_eq!(tree.to_sorted_vec(), before);
+        assert_eq!(
+            (&fused).into_iter().collect::<Vec<_>>(),
+            vec![
+                FoodRange::new(3, 5),
+                FoodRange::new(10, 21),
+                FoodRange::new(22, 25),
+                FoodRange::new(40, 41),
+            ]
+        );
+        assert!(fused.contains(19));
+        assert!(!tree.contains(19));
+        check_tree_structure(&fused);
+
+        let added = fused.insert_persistent(FoodRange::new(30, 30));
+        assert_eq!(fused.size(), 3 + 16 + 2);
+        assert_eq!(added.size(), fused.size() + 1);
+        assert_eq!(tree.size(), 3 + 5 + 6 + 2 + 3);
+
+        //only the path to the new range is copied
+        let left_insert = tree.insert_persistent(FoodRange::new(0, 1));
+        let root_before = tree.node.as_ref().unwrap();
+        let root_after = left_insert.node.as_ref().unwrap();
+        assert!(!Arc::ptr_eq(root_before, root_after));
+        assert!(Arc::ptr_eq(
+            root_before.right.node.as_ref().unwrap(),
+            root_after.right.node.as_ref().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_food_range_shift_scale() {
+        let range = FoodRange::new(10, 20);
+        assert_eq!(range.shift(5), Some(FoodRange::new(15, 25)));
+        assert_eq!(range.shift(-10), Some(FoodRange::new(0, 10)));
+        assert_eq!(range.shift(0), Some(range));
+        assert_eq!(range.shift(-11), None);
+        assert_eq!(FoodRange::new(10, u64::MAX - 3).shift(4), None);
+
+        assert_eq!(range.shift_saturating(5), FoodRange::new(15, 25));
+        assert_eq!(range.shift_saturating(-15), FoodRange::new(0, 5));
+        assert_eq!(range.shift_saturating(0), range);
+        assert_eq!(
+            FoodRange::new(10, u64::MAX - 3).shift_saturating(4),
+            FoodRange::new(14, u64::MAX)
+        );
+        assert_eq!(range.shift_saturating(i64::MIN), FoodRange::new(0, 0));
+
+        assert_eq!(range.scale(3), Some(FoodRange::new(30, 60)));
+        assert_eq!(range.scale(1), Some(range));
+        assert_eq!(range.scale(0), Some(FoodRange::new(0, 0)));
+        assert_eq!(FoodRange::new(1, u64::MAX / 2 + 1).scale(2), None);
+    }
+
+    #[test]
+    fn test_from_sorted_ranges() {
+        let tree = parse_file("./files/test.txt").expect("could not parse").0;
+        let rebuilt = RangeTree::from_sorted_ranges(&tree.to_sorted_vec());
+        check_tree_structure(&rebuilt);
+        assert_eq!(rebuilt, tree);
+        assert_eq!(rebuilt.size(), tree.size());
+        assert_ne!(rebuilt, RangeTree::new());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let tree = parse_file("./files/test.txt").expect("could not parse").0;
+        let json = serde_json::to_string(&tree).expect("could not serialize");
+        assert_eq!(
+            json,
+            r#"[{"lower":3,"upper":5},{"lower":10,"upper":18},{"lower":19,"upper":20}]"#
+        );
+        let deserialized: RangeTree = serde_json::from_str(&json).expect("could not deserialize");
+        assert_eq!(deserialized, tree);
+        check_tree_structure(&deserialized);
+
+        let unsorted = r#"[{"lower":10,"upper":20},{"lower":3,"upper":5}]"#;
+        assert!(serde_json::from_str::<RangeTree>(unsorted).is_err());
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let build = |ranges: &[(u64, u64)]| {
+            let mut tree = RangeTree::new();
+            for &(lower, upper) in ranges {
+                tree.insert(FoodRange::new(lower, upper));
+            }
+            tree
+        };
+        let pairs = [
+            (build(&[(3, 5), (10, 14), (16, 20), (12, 18)]), build(&[])),
+            (build(&[(3, 5), (10, 20)]), build(&[(3, 5), (10, 20)])),
+            (build(&[(1, 10)]), build(&[(3, 4), (6, 6), (9, 12)])),
+            (
+                build(&[(2, 8), (15, 30)]),
+                build(&[(0, 3), (7, 16), (29, 40)]),
+            ),
+            (build(&[(5, 5), (7, 7), (9, 9)]), build(&[(0, 50)])),
+        ];
+        for (tree, other) in &pairs {
+            let overlap_size = (0..=60)
+                .filter(|&id| tree.contains(id) && other.contains(id))
+                .count() as u64;
+            let result = tree.symmetric_difference(other);
+            check_tree_structure(&result);
+            assert_eq!(result.size(), tree.size() + other.size() - 2 * overlap_size);
+            assert_eq!(result, other.symmetric_difference(tree));
+            for id in 0..=60 {
+                assert_eq!(result.contains(id), tree.contains(id) != other.contains(id));
+            }
+        }
+        assert_eq!(
+            pairs[1].0.symmetric_difference(&pairs[1].1),
+            RangeTree::new()
+        );
+    }
 }
@@ -1,13 +1,53 @@
 use anyhow::{Result, anyhow};
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    time::Instant,
-};
+use std::{collections::HashSet, fs, time::Instant};
 
+#[derive(Debug, Clone, PartialEq)]
 enum Op {
     Plus,
     Mult,
+    Sub,
+    Div,
+    Pow,
+    Concat,
+}
+
+impl Op {
+    fn apply(&self, a: u64, b: u64) -> Result<u64> {
+        match self {
+            Op::Plus => Ok(a + b),
+            Op::Mult => Ok(a * b),
+            Op::Sub => Ok(a.saturating_sub(b)),
+            Op::Div => a
+                .checked_div(b)
+                .ok_or(anyhow!("division by zero: {a} / {b}")),
+            Op::Pow => u32::try_from(b)
+                .ok()
+                .and_then(|exp| a.checked_pow(exp))
+                .ok_or(anyhow!("overflow: {a} ^ {b}")),
+            Op::Concat => 10u64
+                .checked_pow(b.checked_ilog10().unwrap_or(0) + 1)
+                .and_then(|shift| a.checked_mul(shift))
+                .and_then(|shifted| shifted.checked_add(b))
+                .ok_or(anyhow!("overflow: {a} | {b}")),
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Op::Plus => "+",
+            Op::Mult => "*",
+            Op::Sub => "-",
+            Op::Div => "/",
+            Op::Pow => "^",
+            Op::Concat => "|",
+        }
+    }
+
+    fn reduce(&self, numbers: impl IntoIterator<Item = u64>) -> Option<Result<u64>> {
+        let mut numbers = numbers.into_iter();
+        let first = numbers.next()?;
+        Some(numbers.try_fold(first, |acc, num| self.apply(acc, num)))
+    }
 }
 
 struct Problem {
@@ -26,30 +66,98 @@ fn get_digits(num: u64) -> Vec<u64> {
 }
 
 impl Problem {
+    fn validate(&self) -> Result<()> {
+        let columns = self.numbers.first().ok_or(anyhow!("no number rows"))?.len();
+        if let Some(line) = self.numbers.iter().position(|row| row.len() != columns) {
+            return Err(anyhow!(
+                "row {line} has {} numbers, expected {columns}",
+                self.numbers[line].len()
+            ));
+        }
+        if self.ops.len() != columns {
+            return Err(anyhow!(
+                "found {} operations for {columns} columns",
+                self.ops.len()
+            ));
+        }
+        //each number must start between its operation and the next one
+        for (index, (op_pos, _)) in self.ops.iter().enumerate() {
+            let next_op_pos = self.ops.get(index + 1).map_or(usize::MAX, |(pos, _)| *pos);
+            for (line, row) in self.numbers.iter().enumerate() {
+                let pos = row[index].0;
+                if pos < *op_pos || pos >= next_op_pos {
+                    return Err(anyhow!(
+                        "number at {pos} on row {line} is not aligned with operation at {op_pos}"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn compute_problem(&self, index: usize) -> Result<u64> {
-        let op = match self.ops[index].1 {
-            Op::Plus => |a, b| a + b,
-            Op::Mult => |a, b| a * b,
-        };
+        self.ops[index]
+            .1
+            .reduce((0..self.numbers.len()).map(|line| self.numbers[line][index].1))
+            .ok_or(anyhow!("not any numbers on column {index}"))?
+    }
 
-        (0..self.numbers.len())
-            .map(|line| self.numbers[line][index].1)
-            .reduce(op)
-            .ok_or(anyhow!("not any numbers on column {index}"))
+    #[allow(dead_code)]
+    //rows have no operation of their own, so the ops of the new columns are given
+    fn transpose(&self, ops: Vec<Op>) -> Result<Problem> {
+        let rows = self.numbers.len();
+        if ops.len() != rows {
+            return Err(anyhow!(
+                "found {} operations for {rows} transposed columns",
+                ops.len()
+            ));
+        }
+        let columns = self.numbers.first().map_or(0, |row| row.len());
+        //left align every number in columns wide enough for the longest one
+        let width = self
+            .numbers
+            .iter()
+            .flatten()
+            .map(|(_, num)| get_digits(*num).len().max(1))
+            .max()
+            .unwrap_or(1)
+            + 1;
+        let numbers = (0..columns)
+            .map(|index| {
+                (0..rows)
+                    .map(|line| (line * width, self.numbers[line][index].1))
+                    .collect()
+            })
+            .collect();
+        let ops = ops
+            .into_iter()
+            .enumerate()
+            .map(|(line, op)| (line * width, op))
+            .collect();
+        Ok(Problem { numbers, ops })
+    }
+
+    #[allow(dead_code)]
+    fn num_distinct_ops(&self) -> usize {
+        self.ops_as_strings()
+            .into_iter()
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn ops_as_strings(&self) -> Vec<&'static str> {
+        self.ops.iter().map(|(_, op)| op.symbol()).collect()
+    }
+
+    fn compute_column_results(&self) -> impl Iterator<Item = Result<u64>> + '_ {
+        (0..self.numbers[0].len()).map(move |index| self.compute_problem(index))
     }
 
     fn compute_total_problems(&self) -> Result<u64> {
-        (0..self.numbers[0].len())
-            .map(|index| self.compute_problem(index))
-            .sum()
+        self.compute_column_results().sum()
     }
 
     fn compute_problem2(&self, index: usize) -> Result<u64> {
-        let op = match self.ops[index].1 {
-            Op::Plus => |a, b| a + b,
-            Op::Mult => |a, b| a * b,
-        };
-
         let op_pos = self.ops[index].0;
 
         let numbers_with_pos = (0..self.numbers.len())
@@ -86,10 +194,10 @@ impl Problem {
                 break;
             }
         }
-        numbers
-            .into_iter()
-            .reduce(op)
-            .ok_or(anyhow!("not any numbers on column {index}"))
+        self.ops[index]
+            .1
+            .reduce(numbers)
+            .ok_or(anyhow!("not any numbers on column {index}"))?
     }
 
     fn compute_total_problems2(&self) -> Result<u64> {
@@ -135,14 +243,15 @@ enum ParseResult {
 }
 
 fn parse(path: &str) -> Result<Problem> {
-    let file = File::open(path)?;
+    let input = fs::read_to_string(path)?;
+    parse_str(&input)
+}
+
+fn parse_str(input: &str) -> Result<Problem> {
     let mut numbers = vec![];
     let mut ops = vec![];
 
-    let parsed_lines = BufReader::new(file)
-        .lines()
-        .map(|s| parse_line(s?.as_str()))
-        .collect::<Result<Vec<_>>>()?;
+    let parsed_lines = input.lines().map(parse_line).collect::<Result<Vec<_>>>()?;
 
     for parsed_line in parsed_lines {
         match parsed_line {
@@ -151,28 +260,30 @@ fn parse(path: &str) -> Result<Problem> {
         }
     }
 
-    Ok(Problem { numbers, ops })
+    let problem = Problem { numbers, ops };
+    problem.validate()?;
+    Ok(problem)
 }
 
 fn parse_line(line: &str) -> Result<ParseResult> {
-    let words = split_whitespace_pos(line);
-    let first_word = words[0].1;
-    if first_word.starts_with(['+', '*']) {
+    let words = split_whitespace_ranges(line);
+    let first_word = &line[words[0].0..words[0].1];
+    if first_word.starts_with(['+', '*', '-', '/', '^', '|']) {
         let mut ops = vec![];
-        for (pos, word) in words {
-            ops.push((pos, parse_op(word)?));
+        for (start, end) in words {
+            ops.push((start, parse_op(&line[start..end])?));
         }
         Ok(ParseResult::Ops(ops))
     } else {
         let mut numbers = vec![];
-        for (pos, word) in words {
-            numbers.push((pos, word.parse::<u64>()?));
+        for (start, end) in words {
+            numbers.push((start, line[start..end].parse::<u64>()?));
         }
         Ok(ParseResult::Numbers(numbers))
     }
 }
 
-fn split_whitespace_pos(line: &str) -> Vec<(usize, &str)> {
+fn split_whitespace_ranges(line: &str) -> Vec<(usize, usize)> {
     let mut out = Vec::new();
     let mut start = None;
 
@@ -180,14 +291,14 @@ fn split_whitespace_pos(line: &str) -> Vec<(usize, &str)> {
         match (start, c.is_whitespace()) {
             (None, false) => start = Some(i),
             (Some(st), true) => {
-                out.push((st, &line[st..i]));
+                out.push((st, i));
                 start = None;
             }
             _ => {}
         }
     }
     if let Some(st) = start {
-        out.push((st, &line[st..]));
+        out.push((st, line.len()));
     }
 
     out
@@ -197,6 +308,10 @@ fn parse_op(op: &str) -> Result<Op> {
     match op {
         "*" => Ok(Op::Mult),
         "+" => Ok(Op::Plus),
+        "-" => Ok(Op::Sub),
+        "/" => Ok(Op::Div),
+        "^" => Ok(Op::Pow),
+        "|" => Ok(Op::Concat),
         _ => Err(anyhow!("{op} is not an operation")),
     }
 }
@@ -205,10 +320,241 @@ fn parse_op(op: &str) -> Result<Op> {
 mod tests {
     use super::*;
 
+    const TEST_INPUT: &str = "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   + \n";
+
     #[test]
     fn test_part() {
         let (part1, part2) = run("./files/test.txt").expect("could not run");
         assert_eq!(&part1, "4277556");
         assert_eq!(&part2, "3263827");
     }
+
+    fn column_problem(column: &[u64], op: Op) -> Problem {
+        Problem {
+            numbers: column.iter().map(|num| vec![(0, *num)]).collect(),
+            ops: vec![(0, op)],
+        }
+    }
+
+    #[test]
+    fn test_ops() {
+        assert_eq!(
+            column_problem(&[20, 5, 3], Op::Sub)
+                .compute_problem(0)
+                .unwrap(),
+            12
+        );
+        assert_eq!(
+            column_problem(&[5, 20], Op::Sub)
+                .compute_problem(0)
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            column_problem(&[100, 5, 3], Op::Div)
+                .compute_problem(0)
+                .unwrap(),
+            6
+        );
+        assert_eq!(
+            column_problem(&[2, 3, 2], Op::Pow)
+                .compute_problem(0)
+                .unwrap(),
+            64
+        );
+        assert_eq!(
+            column_problem(&[12, 0, 345], Op::Concat)
+                .compute_problem(0)
+                .unwrap(),
+            120345
+        );
+
+        assert!(
+            column_problem(&[100, 0], Op::Div)
+                .compute_problem(0)
+                .is_err()
+        );
+        assert!(
+            column_problem(&[10, 20], Op::Pow)
+                .compute_problem(0)
+                .is_err()
+        );
+        assert!(
+            column_problem(&[u64::MAX, 1], Op::Concat)
+                .compute_problem(0)
+                .is_err()
+        );
+
+        //vertical reading gives 13 and 2
+        let problem = column_problem(&[12, 3], Op::Sub);
+        assert_eq!(problem.compute_problem2(0).unwrap(), 11);
+
+        assert!(matches!(parse_op("^"), Ok(Op::Pow)));
+        assert!(matches!(parse_op("|"), Ok(Op::Concat)));
+        assert!(parse_op("%").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let problem = parse_str(TEST_INPUT).expect("could not parse");
+        assert!(problem.validate().is_ok());
+
+        let error = |problem: Problem| problem.validate().unwrap_err().to_string();
+
+        let empty = Problem {
+            numbers: vec![],
+            ops: vec![],
+        };
+        assert_eq!(error(empty), "no number rows");
+
+        let ragged = Problem {
+            numbers: vec![vec![(0, 1), (3, 2)], vec![(0, 3)]],
+            ops: vec![(0, Op::Plus), (3, Op::Plus)],
+        };
+        assert_eq!(error(ragged), "row 1 has 1 numbers, expected 2");
+
+        let missing_op = Problem {
+            numbers: vec![vec![(0, 1), (3, 2)]],
+            ops: vec![(0, Op::Plus)],
+        };
+        assert_eq!(error(missing_op), "found 1 operations for 2 columns");
+
+        let misaligned = Problem {
+            numbers: vec![vec![(0, 1), (3, 2)], vec![(1, 3), (5, 4)]],
+            ops: vec![(0, Op::Plus), (4, Op::Mult)],
+        };
+        assert_eq!(
+            error(misaligned),
+            "number at 3 on row 0 is not aligned with operation at 4"
+        );
+    }
+
+    #[test]
+    fn test_compute_column_results() {
+        let problem = parse_str(TEST_INPUT).expect("could not parse");
+        assert_eq!(
+            problem.compute_column_results().count(),
+            problem.numbers[0].len()
+        );
+        let results: Vec<u64> = problem
+            .compute_column_results()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(results, vec![33210, 490, 4243455, 401]);
+
+        let problem = Problem {
+            numbers: vec![vec![(0, 6), (2, 8), (4, 1)], vec![(0, 3), (2, 0), (4, 2)]],
+            ops: vec![(0, Op::Div), (2, Op::Div), (4, Op::Plus)],
+        };
+        let results: Vec<Result<u64>> = problem.compute_column_results().collect();
+        assert_eq!(results[0].as_ref().unwrap(), &2);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &3);
+        assert!(
+            problem
+                .compute_total_problems()
+                .unwrap_err()
+                .to_string()
+                .contains("division by zero")
+        );
+    }
+
+    #[test]
+    fn test_transpose() {
+        let problem = parse_str(TEST_INPUT).expect("could not parse");
+        let column_ops = problem
+            .ops
+            .iter()
+            .map(|(_, op)| op.clone())
+            .collect::<Vec<_>>();
+        let transposed = problem
+            .transpose(vec![Op::Plus, Op::Mult, Op::Plus])
+            .expect("could not transpose");
+        assert_eq!(transposed.numbers.len(), problem.numbers[0].len());
+        assert_eq!(transposed.numbers[0].len(), problem.numbers.len());
+        assert!(transposed.validate().is_ok());
+        assert_eq!(
+            transposed.numbers[1]
+                .iter()
+                .map(|(_, num)| *num)
+                .collect::<Vec<_>>(),
+            vec![328, 64, 98]
+        );
+        assert_eq!(transposed.ops_as_strings(), vec!["+", "*", "+"]);
+        assert_eq!(
+            transposed.compute_total_problems().unwrap(),
+            (123 + 328 + 51 + 64) + (45 * 64 * 387 * 23) + (6 + 98 + 215 + 314)
+        );
+
+        //giving back the original ops restores the original columns
+        let round_trip = transposed
+            .transpose(column_ops)
+            .expect("could not transpose");
+        assert!(round_trip.validate().is_ok());
+        assert_eq!(round_trip.ops_as_strings(), problem.ops_as_strings());
+        assert_eq!(
+            round_trip
+                .numbers
+                .iter()
+                .map(|row| row.iter().map(|(_, num)| *num).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            problem
+                .numbers
+                .iter()
+                .map(|row| row.iter().map(|(_, num)| *num).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            round_trip.compute_total_problems().unwrap(),
+            problem.compute_total_problems().unwrap()
+        );
+
+        assert!(problem.transpose(vec![Op::Plus]).is_err());
+        assert!(problem.transpose(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_split_whitespace_ranges() {
+        let line = "  6 98  215 314";
+        let ranges = split_whitespace_ranges(line);
+        assert_eq!(ranges, vec![(2, 3), (4, 6), (8, 11), (12, 15)]);
+        let words: Vec<&str> = ranges
+            .iter()
+            .map(|(start, end)| &line[*start..*end])
+            .collect();
+        assert_eq!(words, line.split_whitespace().collect::<Vec<_>>());
+
+        assert_eq!(split_whitespace_ranges("*   + "), vec![(0, 1), (4, 5)]);
+        assert!(split_whitespace_ranges("   ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_str() {
+        let from_str = parse_str(TEST_INPUT).expect("could not parse");
+        let from_file = parse("./files/test.txt").expect("could not parse");
+        assert_eq!(
+            from_str.compute_total_problems().unwrap(),
+            from_file.compute_total_problems().unwrap()
+        );
+        assert_eq!(
+            from_str.compute_total_problems2().unwrap(),
+            from_file.compute_total_problems2().unwrap()
+        );
+        assert_eq!(from_str.compute_total_problems().unwrap(), 4277556);
+        assert_eq!(from_str.compute_total_problems2().unwrap(), 3263827);
+    }
+
+    #[test]
+    fn test_ops_accessors() {
+        let problem = parse_str(TEST_INPUT).expect("could not parse");
+        assert_eq!(problem.num_distinct_ops(), 2);
+        assert_eq!(problem.ops_as_strings(), vec!["*", "+", "*", "+"]);
+        assert_eq!(problem.ops_as_strings().len(), problem.numbers[0].len());
+
+        let problem = parse_str("1 2\n3 4\n- -\n").expect("could not parse");
+        assert_eq!(problem.num_distinct_ops(), 1);
+        for symbol in ["*", "+", "-", "/", "^", "|"] {
+            assert_eq!(parse_op(symbol).unwrap().symbol(), symbol);
+        }
+    }
 }
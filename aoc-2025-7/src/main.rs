@@ -1,12 +1,15 @@
 use anyhow::{Result, anyhow};
+use dashmap::DashMap;
+use rayon::prelude::*;
 use std::{
-    collections::{BTreeMap, HashMap},
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Cursor},
     time::Instant,
 };
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct Manifold {
     source_col: usize,
     splitters: Vec<BTreeMap<usize, bool>>,
@@ -14,19 +17,133 @@ struct Manifold {
 
 impl Manifold {
     fn run_split(&mut self) -> usize {
-        let mut rays = Vec::with_capacity(self.splitters_count());
-        rays.push((0, self.source_col));
-        while let Some((ray_line, ray_col)) = rays.pop() {
-            if let Some((next_splitter_line, has_split)) =
-                self.splitters[ray_col].range_mut(ray_line..).next()
+        self.fire_rays(vec![(0, self.source_col)]);
+        self.splitters_split_count()
+    }
+
+    #[allow(dead_code)]
+    fn run_split_verbose(&mut self) -> Vec<(usize, usize)> {
+        self.fire_rays(vec![(0, self.source_col)])
+    }
+
+    #[allow(dead_code)]
+    fn run_split_from_all_sources(&mut self, sources: &[usize]) -> usize {
+        self.reset();
+        self.fire_rays(sources.iter().map(|source_col| (0, *source_col)).collect());
+        self.splitters_split_count()
+    }
+
+    //rays are processed in BFS order, which is the order of the returned activations
+    fn fire_rays(&mut self, rays: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+        let mut activated = Vec::with_capacity(self.splitters_count());
+        let mut rays = VecDeque::from(rays);
+        rays.reserve(self.splitters_count());
+        while let Some((ray_line, ray_col)) = rays.pop_front() {
+            if let Some((next_splitter_line, has_split)) = self
+                .splitters
+                .get_mut(ray_col)
+                .and_then(|splitters_col| splitters_col.range_mut(ray_line..).next())
                 && !*has_split
             {
                 *has_split = true;
-                rays.push((*next_splitter_line, ray_col - 1));
-                rays.push((*next_splitter_line, ray_col + 1));
+                activated.push((*next_splitter_line, ray_col));
+                if ray_col > 0 {
+                    rays.push_back((*next_splitter_line, ray_col - 1));
+                }
+                rays.push_back((*next_splitter_line, ray_col + 1));
             }
         }
-        self.splitters_split_count()
+        activated
+    }
+
+    #[allow(dead_code)]
+    fn cols_reachable_from(&self, source_col: usize) -> HashSet<usize> {
+        let mut visited = HashSet::from([(0, source_col)]);
+        let mut rays = vec![(0, source_col)];
+        while let Some((ray_line, ray_col)) = rays.pop() {
+            let Some((next_splitter_line, _)) = self
+                .splitters
+                .get(ray_col)
+                .and_then(|splitters_col| splitters_col.range(ray_line..).next())
+            else {
+                continue;
+            };
+            for next_col in [ray_col.checked_sub(1), Some(ray_col + 1)]
+                .into_iter()
+                .flatten()
+                .filter(|next_col| *next_col < self.splitters.len())
+            {
+                if visited.insert((*next_splitter_line, next_col)) {
+                    rays.push((*next_splitter_line, next_col));
+                }
+            }
+        }
+        visited.into_iter().map(|(_, col)| col).collect()
+    }
+
+    #[allow(dead_code)]
+    fn add_splitter(&mut self, line: usize, col: usize) -> Result<()> {
+        let splitters_col = self
+            .splitters
+            .get_mut(col)
+            .ok_or(anyhow!("column {col} is outside the manifold"))?;
+        if splitters_col.contains_key(&line) {
+            return Err(anyhow!(
+                "splitter already exists at line {line}, column {col}"
+            ));
+        }
+        splitters_col.insert(line, false);
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    fn remove_splitter(&mut self, line: usize, col: usize) -> bool {
+        self.splitters
+            .get_mut(col)
+            .is_some_and(|splitters_col| splitters_col.remove(&line).is_some())
+    }
+
+    #[allow(dead_code)]
+    fn set_source_col(&mut self, col: usize) -> Result<()> {
+        if col >= self.splitters.len() {
+            return Err(anyhow!("column {col} is outside the manifold"));
+        }
+        self.source_col = col;
+        self.reset();
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    fn optimal_source_col(&self) -> usize {
+        (0..self.col_count())
+            .map(|col| {
+                let mut manifold = self.clone();
+                manifold.source_col = col;
+                manifold.reset();
+                (col, manifold.run_split())
+            })
+            .max_by_key(|(col, count)| (*count, Reverse(*col)))
+            .map(|(col, _)| col)
+            .unwrap_or(self.source_col)
+    }
+
+    fn reset(&mut self) {
+        for has_split in self
+            .splitters
+            .iter_mut()
+            .flat_map(|splitters_col| splitters_col.values_mut())
+        {
+            *has_split = false;
+        }
+    }
+
+    fn col_count(&self) -> usize {
+        self.splitters.len()
+    }
+
+    //trailing lines without splitters are not counted
+    fn row_count(&self) -> usize {
+        self.final_line()
     }
 
     fn splitters_count(&self) -> usize {
@@ -48,6 +165,36 @@ impl Manifold {
             .sum()
     }
 
+    #[allow(dead_code)]
+    fn density(&self) -> f64 {
+        if self.splitters_count() == 0 {
+            return 0.0;
+        }
+        self.splitters_count() as f64 / (self.col_count() * self.row_count()) as f64
+    }
+
+    #[allow(dead_code)]
+    fn splits_by_col(&self) -> Vec<usize> {
+        self.splitters
+            .iter()
+            .map(|splitters_col| {
+                splitters_col
+                    .values()
+                    .filter(|has_split| **has_split)
+                    .count()
+            })
+            .collect()
+    }
+
+    #[allow(dead_code)]
+    fn split_density(&self) -> f64 {
+        if self.splitters_count() == 0 {
+            return 0.0;
+        }
+        self.splitters_split_count() as f64 / self.splitters_count() as f64
+    }
+
+    //a manifold without splitters still has the source line
     fn final_line(&self) -> usize {
         self.splitters
             .iter()
@@ -57,8 +204,23 @@ impl Manifold {
                     .map(|(last_line, _)| *last_line)
             })
             .max()
-            .unwrap()
-            + 1
+            .map_or(1, |last_line| last_line + 1)
+    }
+
+    #[allow(dead_code)]
+    fn to_ascii(&self) -> String {
+        let mut grid = vec![vec!['.'; self.splitters.len()]; self.final_line()];
+        grid[0][self.source_col] = 'S';
+        //a splitter under the source catches the ray right away, so it wins
+        for (col, splitters_col) in self.splitters.iter().enumerate() {
+            for line in splitters_col.keys() {
+                grid[*line][col] = '^';
+            }
+        }
+        grid.into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
     fn get_timelines_count_all(&self) -> usize {
@@ -72,53 +234,87 @@ impl Manifold {
         final_count
     }
 
+    #[allow(dead_code)]
+    fn get_timelines_count_all_parallel(&self) -> usize {
+        let already_computed = DashMap::new();
+        let final_line = self.final_line();
+        (0..self.splitters.len())
+            .into_par_iter()
+            .map(|col| self.get_timelines_count_shared((final_line, col), &already_computed))
+            .sum()
+    }
+
     fn get_timelines_count_memoized(
         &self,
         destination: (usize, usize),
         already_computed: &mut HashMap<(usize, usize), usize>,
     ) -> usize {
         if let Some(result) = already_computed.get(&destination) {
-            *result
-        } else {
-            let mut count = 0;
-            let (line, col) = destination;
-            let min_line = self.splitters[col]
-                .range(..line)
-                .next_back()
-                .map(|(splitter_line, _)| *splitter_line)
-                .unwrap_or(0);
-
-            if col > 0 {
-                count = self.splitters[col - 1]
-                    .range(min_line..line)
-                    .map(|(splitter_left_line, _)| {
-                        self.get_timelines_count_memoized(
-                            (*splitter_left_line, col - 1),
-                            already_computed,
-                        )
-                    })
-                    .sum::<usize>();
-            }
+            return *result;
+        }
 
-            if col < self.splitters.len() - 1 {
-                count += self.splitters[col + 1]
-                    .range(min_line..line)
-                    .map(|(splitter_right_line, _)| {
-                        self.get_timelines_count_memoized(
-                            (*splitter_right_line, col + 1),
-                            already_computed,
-                        )
-                    })
-                    .sum::<usize>();
-            }
+        let (predecessors, from_source) = self.get_timelines_predecessors(destination);
+        let count = predecessors
+            .into_iter()
+            .map(|predecessor| self.get_timelines_count_memoized(predecessor, already_computed))
+            .sum::<usize>()
+            + from_source;
 
-            if min_line == 0 && col == self.source_col {
-                count += 1
-            }
+        already_computed.insert(destination, count);
+        count
+    }
+
+    //entry() would keep the shard locked while recursing, so lookup and insert are separate
+    fn get_timelines_count_shared(
+        &self,
+        destination: (usize, usize),
+        already_computed: &DashMap<(usize, usize), usize>,
+    ) -> usize {
+        if let Some(result) = already_computed.get(&destination) {
+            return *result;
+        }
+
+        let (predecessors, from_source) = self.get_timelines_predecessors(destination);
+        let count = predecessors
+            .into_iter()
+            .map(|predecessor| self.get_timelines_count_shared(predecessor, already_computed))
+            .sum::<usize>()
+            + from_source;
 
-            already_computed.insert(destination, count);
-            count
+        already_computed.insert(destination, count);
+        count
+    }
+
+    fn get_timelines_predecessors(
+        &self,
+        destination: (usize, usize),
+    ) -> (Vec<(usize, usize)>, usize) {
+        let (line, col) = destination;
+        let min_line = self.splitters[col]
+            .range(..line)
+            .next_back()
+            .map(|(splitter_line, _)| *splitter_line)
+            .unwrap_or(0);
+
+        let mut predecessors = vec![];
+        if col > 0 {
+            predecessors.extend(
+                self.splitters[col - 1]
+                    .range(min_line..line)
+                    .map(|(splitter_left_line, _)| (*splitter_left_line, col - 1)),
+            );
         }
+
+        if col < self.splitters.len() - 1 {
+            predecessors.extend(
+                self.splitters[col + 1]
+                    .range(min_line..line)
+                    .map(|(splitter_right_line, _)| (*splitter_right_line, col + 1)),
+            );
+        }
+
+        let from_source = usize::from(min_line == 0 && col == self.source_col);
+        (predecessors, from_source)
     }
 }
 
@@ -153,10 +349,19 @@ fn part2(manifold: &Manifold) -> usize {
 }
 
 fn parse_file(path: &str) -> Result<Manifold> {
+    let file = File::open(path)?;
+    parse(BufReader::new(file))
+}
+
+#[allow(dead_code)]
+fn from_grid(grid: &str) -> Result<Manifold> {
+    parse(Cursor::new(grid.as_bytes()))
+}
+
+fn parse<R: BufRead>(reader: R) -> Result<Manifold> {
     let mut source_col: usize = 0;
     let mut splitters: Vec<BTreeMap<usize, bool>> = vec![];
-    let file = File::open(path)?;
-    for (line_idx, line) in BufReader::new(file).lines().enumerate() {
+    for (line_idx, line) in reader.lines().enumerate() {
         for (col_idx, ch) in line?.char_indices() {
             if line_idx == 0 {
                 splitters.push(BTreeMap::new());
@@ -166,13 +371,83 @@ fn parse_file(path: &str) -> Result<Manifold> {
                     source_col = col_idx;
                 }
                 '^' => {
-                    splitters[col_idx].insert(line_idx, false);
+                    splitters
+                        .get_mut(col_idx)
+                        .ok_or(anyhow!("column {col_idx} is outside the manifold"))?
+                        .insert(line_idx, false);
                 }
                 '.' => {}
                 ch => return Err(anyhow!("unexpected char {ch}")),
             }
         }
     }
+    if source_col >= splitters.len() {
+        return Err(anyhow!(
+            "source column {source_col} is outside the manifold"
+        ));
+    }
+    Ok(Manifold {
+        source_col,
+        splitters,
+    })
+}
+
+#[allow(dead_code)]
+fn parse_file_csv(path: &str) -> Result<Manifold> {
+    let file = File::open(path)?;
+    parse_csv(BufReader::new(file))
+}
+
+fn parse_csv<R: BufRead>(reader: R) -> Result<Manifold> {
+    let mut lines = reader.lines();
+
+    let header = lines.next().ok_or(anyhow!("missing header"))??;
+    let mut source_col = None;
+    let mut col_count = None;
+    for field in header.split(',') {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or(anyhow!("invalid header field {field}"))?;
+        let value = value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| anyhow!("could not parse header value {value}"))?;
+        match key.trim() {
+            "source_col" => source_col = Some(value),
+            "col_count" => col_count = Some(value),
+            key => return Err(anyhow!("unexpected header key {key}")),
+        }
+    }
+    let source_col = source_col.ok_or(anyhow!("missing source_col in header"))?;
+    let col_count = col_count.ok_or(anyhow!("missing col_count in header"))?;
+
+    let mut splitters: Vec<BTreeMap<usize, bool>> = vec![BTreeMap::new(); col_count];
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (line_idx, col_idx) = line
+            .split_once(',')
+            .ok_or(anyhow!("invalid splitter {line}"))?;
+        let line_idx = line_idx
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| anyhow!("could not parse line index {line_idx}"))?;
+        let col_idx = col_idx
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| anyhow!("could not parse column index {col_idx}"))?;
+        splitters
+            .get_mut(col_idx)
+            .ok_or(anyhow!("column {col_idx} is outside the manifold"))?
+            .insert(line_idx, false);
+    }
+    if source_col >= splitters.len() {
+        return Err(anyhow!(
+            "source column {source_col} is outside the manifold"
+        ));
+    }
     Ok(Manifold {
         source_col,
         splitters,
@@ -183,10 +458,301 @@ fn parse_file(path: &str) -> Result<Manifold> {
 mod tests {
     use super::*;
 
+    const TEST_GRID: &str = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+
     #[test]
     fn test_part() {
         let (part1, part2) = run("./files/test.txt").expect("could not run");
         assert_eq!(&part1, "21");
         assert_eq!(&part2, "40");
     }
+
+    #[test]
+    fn test_from_grid() {
+        let content = std::fs::read_to_string("./files/test.txt").expect("could not read");
+        let mut manifold = from_grid(&content).expect("could not parse");
+        let mut from_file = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(manifold, from_file);
+        assert_eq!(from_grid(TEST_GRID).expect("could not parse"), from_file);
+        assert_eq!(manifold.run_split(), from_file.run_split());
+        assert_eq!(
+            manifold.get_timelines_count_all(),
+            from_file.get_timelines_count_all()
+        );
+
+        assert!(from_grid("..S..\n..x..\n").is_err());
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        let first = manifold.run_split();
+        assert_eq!(manifold.run_split(), first);
+
+        manifold.reset();
+        assert_eq!(manifold.splitters_split_count(), 0);
+        manifold.reset();
+        assert_eq!(manifold.splitters_split_count(), 0);
+        assert_eq!(manifold.run_split(), first);
+    }
+
+    #[test]
+    fn test_run_split_from_all_sources() {
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        let all_cols: Vec<usize> = (0..manifold.splitters.len()).collect();
+        assert_eq!(manifold.run_split_from_all_sources(&all_cols), 21);
+        assert_eq!(manifold.run_split_from_all_sources(&[0]), 0);
+        assert_eq!(manifold.run_split_from_all_sources(&[3]), 4);
+        assert_eq!(manifold.run_split_from_all_sources(&[3, 7]), 21);
+
+        let single = manifold.run_split_from_all_sources(&[manifold.source_col]);
+        manifold.reset();
+        assert_eq!(single, manifold.run_split());
+    }
+
+    #[test]
+    fn test_cols_reachable_from() {
+        let manifold = from_grid(TEST_GRID).expect("could not parse");
+        assert_eq!(
+            manifold.cols_reachable_from(manifold.source_col),
+            HashSet::from_iter(0..manifold.splitters.len())
+        );
+        assert_eq!(
+            manifold.cols_reachable_from(3),
+            HashSet::from([0, 1, 2, 3, 4])
+        );
+        assert_eq!(manifold.cols_reachable_from(0), HashSet::from([0]));
+    }
+
+    #[test]
+    fn test_timelines_parallel() {
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        for source_col in [manifold.source_col, 3, 0] {
+            manifold.source_col = source_col;
+            assert_eq!(
+                manifold.get_timelines_count_all_parallel(),
+                manifold.get_timelines_count_all()
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let manifold = from_grid(TEST_GRID).expect("could not parse");
+        let expected = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.";
+        let ascii = manifold.to_ascii();
+        assert_eq!(ascii, expected);
+        assert_eq!(ascii.matches('^').count(), manifold.splitters_count());
+
+        let mut manifold = from_grid("S..\n...\n").expect("could not parse");
+        assert_eq!(manifold.to_ascii(), "S..");
+        manifold.add_splitter(0, 0).expect("could not add");
+        assert_eq!(manifold.to_ascii(), "^..");
+        assert_eq!(manifold.run_split(), 1);
+        assert!(manifold.remove_splitter(0, 0));
+        assert_eq!(manifold.to_ascii(), "S..");
+        assert_eq!(manifold.run_split(), 0);
+        assert_eq!(manifold.get_timelines_count_all(), 1);
+    }
+
+    #[test]
+    fn test_add_remove_splitter() {
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        let original = manifold.get_timelines_count_all();
+
+        manifold.add_splitter(14, 11).expect("could not add");
+        assert_eq!(manifold.get_timelines_count_all(), 41);
+        assert!(manifold.add_splitter(14, 11).is_err());
+        assert!(manifold.add_splitter(2, manifold.splitters.len()).is_err());
+
+        assert!(manifold.remove_splitter(14, 11));
+        assert!(!manifold.remove_splitter(14, 11));
+        assert!(!manifold.remove_splitter(2, manifold.splitters.len()));
+        assert_eq!(manifold.get_timelines_count_all(), original);
+    }
+
+    #[test]
+    fn test_run_split_verbose() {
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        let trace = manifold.run_split_verbose();
+        assert_eq!(
+            trace,
+            vec![
+                (2, 7),
+                (4, 6),
+                (4, 8),
+                (6, 5),
+                (6, 7),
+                (6, 9),
+                (8, 4),
+                (8, 6),
+                (8, 10),
+                (10, 3),
+                (10, 5),
+                (14, 7),
+                (10, 9),
+                (10, 11),
+                (12, 2),
+                (12, 6),
+                (12, 12),
+                (14, 1),
+                (14, 3),
+                (14, 5),
+                (14, 13),
+            ]
+        );
+        manifold.reset();
+        assert_eq!(trace.len(), manifold.run_split());
+    }
+
+    #[test]
+    fn test_source_col() {
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        manifold.run_split();
+        manifold.set_source_col(5).expect("could not set source");
+        assert_eq!(manifold.splitters_split_count(), 0);
+
+        let mut fresh = from_grid(TEST_GRID).expect("could not parse");
+        fresh.source_col = 5;
+        assert_eq!(manifold.run_split(), fresh.run_split());
+        assert_eq!(manifold.run_split(), 11);
+
+        assert!(manifold.set_source_col(manifold.splitters.len()).is_err());
+        assert_eq!(manifold.source_col, 5);
+        assert_eq!(manifold.optimal_source_col(), 7);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let manifold = from_grid(TEST_GRID).expect("could not parse");
+        let mut csv = format!(
+            "source_col={},col_count={}\n",
+            manifold.source_col,
+            manifold.splitters.len()
+        );
+        for (col, splitters_col) in manifold.splitters.iter().enumerate() {
+            for line in splitters_col.keys() {
+                csv.push_str(&format!("{line},{col}\n"));
+            }
+        }
+        let parsed = parse_csv(Cursor::new(csv.as_bytes())).expect("could not parse");
+        assert_eq!(parsed, manifold);
+        assert_eq!(parsed.clone().run_split(), manifold.clone().run_split());
+        assert_eq!(
+            parsed.get_timelines_count_all(),
+            manifold.get_timelines_count_all()
+        );
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let manifold = from_grid(TEST_GRID).expect("could not parse");
+        assert_eq!(manifold.col_count(), 15);
+        assert_eq!(manifold.row_count(), 15);
+        assert_eq!(manifold.to_ascii().lines().count(), manifold.row_count());
+
+        let empty = from_grid("S..\n...\n").expect("could not parse");
+        assert_eq!(empty.col_count(), 3);
+        assert_eq!(empty.row_count(), 1);
+        assert_eq!(empty.density(), 0.0);
+
+        let optimal = manifold.optimal_source_col();
+        assert_eq!(optimal, 7);
+        let mut default = manifold.clone();
+        let mut best = manifold.clone();
+        best.set_source_col(optimal).expect("could not set source");
+        assert!(best.run_split() >= default.run_split());
+        for col in 0..manifold.col_count() {
+            let mut other = manifold.clone();
+            other.set_source_col(col).expect("could not set source");
+            assert!(best.clone().run_split() >= other.run_split());
+        }
+    }
+
+    #[test]
+    fn test_density() {
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        assert_eq!(manifold.density(), 22.0 / (15.0 * 15.0));
+        assert_eq!(manifold.split_density(), 0.0);
+        assert_eq!(manifold.splits_by_col(), vec![0; manifold.col_count()]);
+
+        for source_col in [manifold.source_col, 3, 0] {
+            manifold
+                .set_source_col(source_col)
+                .expect("could not set source");
+            manifold.run_split();
+            let splits_by_col = manifold.splits_by_col();
+            assert_eq!(splits_by_col.len(), manifold.col_count());
+            assert_eq!(
+                splits_by_col.iter().sum::<usize>(),
+                manifold.splitters_split_count()
+            );
+            for density in [manifold.density(), manifold.split_density()] {
+                assert!((0.0..=1.0).contains(&density));
+            }
+        }
+
+        manifold.set_source_col(7).expect("could not set source");
+        manifold.run_split();
+        assert_eq!(manifold.split_density(), 21.0 / 22.0);
+        assert_eq!(
+            manifold.splits_by_col(),
+            vec![0, 1, 1, 2, 1, 3, 3, 3, 1, 2, 1, 1, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_source_col_bounds() {
+        assert!(from_grid("...\n...S\n").is_err());
+        assert!(from_grid("").is_err());
+        assert!(from_grid("..S\n...^\n").is_err());
+
+        let manifold = from_grid("S..\n.^.\n").expect("could not parse");
+        assert_eq!(manifold.source_col, 0);
+        let manifold = from_grid("..S\n.^.\n").expect("could not parse");
+        assert_eq!(manifold.source_col, 2);
+
+        let mut manifold = from_grid(TEST_GRID).expect("could not parse");
+        let col_count = manifold.col_count();
+        assert!(manifold.set_source_col(col_count).is_err());
+        manifold.set_source_col(0).expect("could not set source");
+        assert_eq!(manifold.run_split(), 0);
+        manifold
+            .set_source_col(col_count - 1)
+            .expect("could not set source");
+        assert_eq!(manifold.source_col, col_count - 1);
+        assert_eq!(manifold.run_split(), 0);
+    }
 }
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use anyhow::anyhow;
+use rayon::prelude::*;
 use std::{
     fs::File,
     io::{BufRead, BufReader},
@@ -21,21 +22,58 @@ impl Point {
         )
         .norm()
     }
+
+    #[allow(dead_code)]
+    fn manhattan_distance(&self, point: &Point) -> u128 {
+        self.0.abs_diff(point.0) + self.1.abs_diff(point.1) + self.2.abs_diff(point.2)
+    }
+
+    #[allow(dead_code)]
+    fn chebyshev_distance(&self, point: &Point) -> u128 {
+        self.0
+            .abs_diff(point.0)
+            .max(self.1.abs_diff(point.1))
+            .max(self.2.abs_diff(point.2))
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Eq)]
 struct Pair {
     first: usize,
     second: usize,
     dist: u128,
 }
 
+impl PartialEq for Pair {
+    fn eq(&self, other: &Self) -> bool {
+        ((self.first == other.first && self.second == other.second)
+            || (self.first == other.second && self.second == other.first))
+            && self.dist == other.dist
+    }
+}
+
+impl std::fmt::Display for Pair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}: {}", self.first, self.second, self.dist)
+    }
+}
+
 impl Pair {
     fn new(point1: &Point, point2: &Point, index1: usize, index2: usize) -> Pair {
+        Pair::with_metric(point1, point2, index1, index2, Point::distance)
+    }
+
+    fn with_metric(
+        point1: &Point,
+        point2: &Point,
+        index1: usize,
+        index2: usize,
+        metric: fn(&Point, &Point) -> u128,
+    ) -> Pair {
         Pair {
             first: index1,
             second: index2,
-            dist: point1.distance(point2),
+            dist: metric(point1, point2),
         }
     }
 }
@@ -87,8 +125,17 @@ impl Kruskal {
             .filter_map(|(_, x)| Some(self.sizes[*x]).take_if(|v| *v != 1))
             .collect()
     }
+
+    fn count_forests(&self) -> usize {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(|(parent, x)| **x == *parent)
+            .count()
+    }
 }
 
+#[derive(Debug, Clone, Default)]
 struct Network {
     points: Vec<Point>,
     edges: Vec<Pair>,
@@ -97,10 +144,18 @@ struct Network {
 
 impl Network {
     fn new(points: Vec<Point>, count_pairs: usize) -> Self {
+        Network::with_metric(points, count_pairs, Point::distance)
+    }
+
+    fn with_metric(
+        points: Vec<Point>,
+        count_pairs: usize,
+        metric: fn(&Point, &Point) -> u128,
+    ) -> Self {
         let mut edges: Vec<Pair> = (0..points.len())
             .flat_map(|i| {
                 (i + 1..points.len())
-                    .map(|j| Pair::new(&points[i], &points[j], i, j))
+                    .map(|j| Pair::with_metric(&points[i], &points[j], i, j, metric))
                     .collect::<Vec<_>>()
             })
             .collect();
@@ -113,6 +168,26 @@ impl Network {
         }
     }
 
+    #[allow(dead_code)]
+    fn new_parallel(points: Vec<Point>, count_pairs: usize) -> Self {
+        let mut edges: Vec<Pair> = {
+            let points = &points;
+            (0..points.len())
+                .into_par_iter()
+                .flat_map_iter(|i| {
+                    (i + 1..points.len()).map(move |j| Pair::new(&points[i], &points[j], i, j))
+                })
+                .collect()
+        };
+        edges.par_sort_by_key(|pair| pair.dist);
+
+        Network {
+            points,
+            edges,
+            count_pairs,
+        }
+    }
+
     fn kruskal_limited_part1(&self) -> usize {
         let mut kruskal = Kruskal::with_capacity(self.points.len());
 
@@ -148,6 +223,98 @@ impl Network {
         }
         Err(anyhow!("graph does not have a spanning tree"))
     }
+
+    //squared euclidean distance whatever the metric of the edges, checking every pair
+    //the farthest pair lies on the 3d convex hull, which would avoid the O(n²) scan
+    #[allow(dead_code)]
+    fn diameter(&self) -> Option<u128> {
+        if self.points.is_empty() {
+            return None;
+        }
+        (0..self.points.len())
+            .flat_map(|i| (i..self.points.len()).map(move |j| (i, j)))
+            .map(|(i, j)| self.points[i].distance(&self.points[j]))
+            .max()
+    }
+
+    #[allow(dead_code)]
+    fn centroid(&self) -> Option<(f64, f64, f64)> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let (x, y, z) = self
+            .points
+            .iter()
+            .fold((0u128, 0u128, 0u128), |(x, y, z), point| {
+                (x + point.0, y + point.1, z + point.2)
+            });
+        let count = self.points.len() as f64;
+        Some((x as f64 / count, y as f64 / count, z as f64 / count))
+    }
+
+    #[allow(dead_code)]
+    fn cluster_count(&self, threshold: u128) -> usize {
+        //the edges may use another metric, so connectivity is computed from the points
+        let mut kruskal = Kruskal::with_capacity(self.points.len());
+
+        for i in 0..self.points.len() {
+            for j in (i + 1)..self.points.len() {
+                if self.points[i].distance(&self.points[j]) <= threshold {
+                    kruskal.union(i, j);
+                }
+            }
+        }
+        kruskal.count_forests()
+    }
+
+    #[allow(dead_code)]
+    fn remove_point(&mut self, point: &Point) -> bool {
+        let Some(index) = self.points.iter().position(|other| other == point) else {
+            return false;
+        };
+        self.points.remove(index);
+        self.edges
+            .retain(|pair| pair.first != index && pair.second != index);
+        for pair in self.edges.iter_mut() {
+            if pair.first > index {
+                pair.first -= 1;
+            }
+            if pair.second > index {
+                pair.second -= 1;
+            }
+        }
+        true
+    }
+
+    #[allow(dead_code)]
+    fn minimum_spanning_tree(&self) -> Vec<Pair> {
+        let mut kruskal = Kruskal::with_capacity(self.points.len());
+        let mut tree = Vec::with_capacity(self.points.len().saturating_sub(1));
+
+        for edge in self.edges.iter() {
+            if tree.len() + 1 >= self.points.len() {
+                break;
+            }
+
+            let x = kruskal.find(edge.first);
+            let y = kruskal.find(edge.second);
+
+            if x != y {
+                kruskal.union(x, y);
+                tree.push(edge.clone());
+            }
+        }
+        tree
+    }
+}
+
+impl IntoIterator for Network {
+    type Item = Point;
+    type IntoIter = std::vec::IntoIter<Point>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.points.into_iter()
+    }
 }
 
 fn main() {
@@ -215,4 +382,148 @@ mod tests {
         assert_eq!(&part1, "40");
         assert_eq!(&part2, "25272");
     }
+
+    #[test]
+    fn test_kruskal() {
+        let mut kruskal = Kruskal::with_capacity(6);
+        kruskal.union(0, 1);
+        kruskal.union(1, 2);
+        kruskal.union(3, 4);
+        assert_eq!(kruskal.find(2), kruskal.find(0));
+        assert_ne!(kruskal.find(3), kruskal.find(0));
+        assert_eq!(kruskal.find(5), 5);
+
+        let mut size_forests = kruskal.size_forests();
+        size_forests.sort();
+        assert_eq!(size_forests, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree() {
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let tree = network.minimum_spanning_tree();
+        assert_eq!(tree.len(), network.points.len() - 1);
+
+        let mut kruskal = Kruskal::with_capacity(network.points.len());
+        for edge in tree.iter() {
+            kruskal.union(edge.first, edge.second);
+        }
+        assert_eq!(kruskal.size_forests(), vec![network.points.len()]);
+
+        assert_eq!(tree.iter().map(|edge| edge.dist).sum::<u128>(), 2596246);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let point1 = Point(1, 8, 3);
+        let point2 = Point(4, 2, 5);
+        assert_eq!(point1.distance(&point2), 49);
+        assert_eq!(point1.manhattan_distance(&point2), 11);
+        assert_eq!(point1.chebyshev_distance(&point2), 6);
+
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let generic = Network::with_metric(network.points.clone(), 10, Point::distance);
+        assert_eq!(generic.edges, network.edges);
+
+        let manhattan = Network::with_metric(network.points.clone(), 10, Point::manhattan_distance);
+        assert!(manhattan.edges.is_sorted_by_key(|pair| pair.dist));
+        for pair in manhattan.edges.iter() {
+            let point1 = network.points[pair.first];
+            let point2 = network.points[pair.second];
+            assert_eq!(pair.dist, point1.manhattan_distance(&point2));
+        }
+    }
+
+    #[test]
+    fn test_new_parallel() {
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let parallel = Network::new_parallel(network.points.clone(), network.count_pairs);
+        assert_eq!(parallel.edges, network.edges);
+        assert_eq!(
+            parallel.kruskal_limited_part1(),
+            network.kruskal_limited_part1()
+        );
+    }
+
+    #[test]
+    fn test_pair() {
+        let pair = Pair {
+            first: 1,
+            second: 4,
+            dist: 12,
+        };
+        let reversed = Pair {
+            first: 4,
+            second: 1,
+            dist: 12,
+        };
+        let other = Pair {
+            first: 1,
+            second: 4,
+            dist: 13,
+        };
+        assert_eq!(pair, reversed);
+        assert_ne!(pair, other);
+        assert_eq!(pair.to_string(), "1-4: 12");
+    }
+
+    #[test]
+    fn test_diameter_centroid() {
+        let network = Network::new(vec![Point(0, 0, 0), Point(2, 0, 0)], 0);
+        assert_eq!(network.diameter(), Some(4));
+        assert_eq!(network.centroid(), Some((1.0, 0.0, 0.0)));
+
+        let single = Network::new(vec![Point(1, 2, 3)], 0);
+        assert_eq!(single.diameter(), Some(0));
+        assert_eq!(single.centroid(), Some((1.0, 2.0, 3.0)));
+
+        let points = vec![Point(0, 0, 0), Point(3, 3, 0), Point(5, 0, 0)];
+        for network in [
+            Network::new(points.clone(), 0),
+            Network::with_metric(points.clone(), 3, Point::manhattan_distance),
+        ] {
+            assert_eq!(network.diameter(), Some(25));
+        }
+
+        let empty = Network::new(vec![], 0);
+        assert_eq!(empty.diameter(), None);
+        assert_eq!(empty.centroid(), None);
+    }
+
+    #[test]
+    fn test_cluster_count() {
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        assert_eq!(network.cluster_count(0), network.points.len());
+        assert_eq!(network.cluster_count(u128::MAX), 1);
+        assert_eq!(network.cluster_count(124564), 11);
+        assert_eq!(network.cluster_count(150000), 5);
+        assert_eq!(network.cluster_count(200000), 2);
+
+        let manhattan = Network::with_metric(network.points.clone(), 10, Point::manhattan_distance);
+        assert_eq!(manhattan.cluster_count(0), network.points.len());
+        assert_eq!(manhattan.cluster_count(124564), 11);
+        assert_eq!(manhattan.cluster_count(150000), 5);
+        assert_eq!(manhattan.cluster_count(200000), 2);
+    }
+
+    #[test]
+    fn test_network_traits() {
+        let network = parse_file("./files/test.txt").expect("could not parse");
+        let mut clone = network.clone();
+        let removed = network.points[3];
+        assert!(clone.remove_point(&removed));
+        assert!(!clone.remove_point(&removed));
+        assert_eq!(clone.points.len(), network.points.len() - 1);
+        assert_eq!(network.points[3], removed);
+
+        let rebuilt = Network::new(clone.points.clone(), clone.count_pairs);
+        assert_eq!(clone.edges, rebuilt.edges);
+
+        let empty = Network::default();
+        assert!(empty.points.is_empty());
+        assert!(empty.edges.is_empty());
+
+        let points: Vec<Point> = network.clone().into_iter().collect();
+        assert_eq!(points, network.points);
+    }
 }
@@ -2,11 +2,9 @@ use anyhow::Result;
 use anyhow::anyhow;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    time::Instant,
-};
+use std::fmt::Write;
+use std::ops::{Add, Sub};
+use std::{fs, time::Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 struct Point(u64, u64);
@@ -19,6 +17,44 @@ impl Point {
     fn get_other_corners(&self, point: &Point) -> (Point, Point) {
         (Point(self.0, point.1), Point(point.0, self.1))
     }
+
+    #[allow(dead_code)]
+    fn manhattan_distance(&self, other: &Point) -> u64 {
+        self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
+    }
+
+    #[allow(dead_code)]
+    fn chebyshev_distance(&self, other: &Point) -> u64 {
+        self.0.abs_diff(other.0).max(self.1.abs_diff(other.1))
+    }
+}
+
+impl Add<Point> for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point(
+            self.0.saturating_add(other.0),
+            self.1.saturating_add(other.1),
+        )
+    }
+}
+
+impl Sub<Point> for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point(
+            self.0.saturating_sub(other.0),
+            self.1.saturating_sub(other.1),
+        )
+    }
+}
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.0, self.1)
+    }
 }
 
 enum PositionToWall {
@@ -53,6 +89,7 @@ impl YWall {
         }
     }
 
+    #[cfg(test)]
     fn intersects(&self, x: u64) -> bool {
         (self.x1..=self.x2).contains(&x)
     }
@@ -173,8 +210,55 @@ impl Walls {
                 y_wall.intersects_middle(min_x) || y_wall.intersects_middle(max_x)
             })
     }
+
+    #[allow(dead_code)]
+    fn to_svg(&self) -> String {
+        //the bounds are not set when there are no walls
+        if self.x_walls.is_empty() && self.y_walls.is_empty() {
+            return "<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>".to_owned();
+        }
+        let min_y = self
+            .x_walls
+            .values()
+            .map(|x_wall| x_wall.y1)
+            .min()
+            .unwrap_or(0)
+            .saturating_sub(1);
+        let max_y = self
+            .x_walls
+            .values()
+            .map(|x_wall| x_wall.y2)
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+            self.x_left,
+            min_y,
+            self.x_right - self.x_left,
+            max_y - min_y
+        );
+        for (x, x_wall) in self.x_walls.iter() {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"black\" />",
+                x_wall.y1, x_wall.y2
+            );
+        }
+        for (y, y_wall) in self.y_walls.iter() {
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\" stroke=\"black\" />",
+                y_wall.x1, y_wall.x2
+            );
+        }
+        svg.push_str("</svg>");
+        svg
+    }
 }
 
+#[derive(Debug, PartialEq)]
 struct Grid {
     points: Vec<Point>,
 }
@@ -184,6 +268,11 @@ impl Grid {
         Grid { points }
     }
 
+    #[allow(dead_code)]
+    fn from_points(points: Vec<Point>) -> Self {
+        Grid::new(points)
+    }
+
     fn get_max_area(&self) -> u64 {
         (0..self.points.len())
             .map(|i| {
@@ -257,6 +346,60 @@ impl Grid {
             .max_by_key(|(_, _, v)| *v)
             .unwrap_or((Point(0, 0), Point(0, 0), 0))
     }
+
+    #[cfg(test)]
+    fn is_inside_flood_fill(&self, walls: &Walls, point: Point) -> bool {
+        use std::collections::{HashSet, VecDeque};
+
+        let is_on_wall = |point: Point| {
+            walls
+                .x_walls
+                .get(&point.0)
+                .is_some_and(|x_wall| x_wall.intersects(point.1))
+                || walls
+                    .y_walls
+                    .get(&point.1)
+                    .is_some_and(|y_wall| y_wall.intersects(point.0))
+        };
+        if is_on_wall(point) {
+            return true;
+        }
+
+        let min_x = walls.x_left;
+        let max_x = walls.x_right;
+        let min_y = self
+            .points
+            .iter()
+            .map(|point| point.1)
+            .min()
+            .unwrap_or(0)
+            .saturating_sub(1);
+        let max_y = self.points.iter().map(|point| point.1).max().unwrap_or(0) + 1;
+        if point.0 <= min_x || point.0 >= max_x || point.1 <= min_y || point.1 >= max_y {
+            return false;
+        }
+
+        let start = Point(min_x, min_y);
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(Point(x, y)) = queue.pop_front() {
+            if Point(x, y) == point {
+                return false;
+            }
+            let neighbours = [
+                (x > min_x).then(|| Point(x - 1, y)),
+                (x < max_x).then(|| Point(x + 1, y)),
+                (y > min_y).then(|| Point(x, y - 1)),
+                (y < max_y).then(|| Point(x, y + 1)),
+            ];
+            for neighbour in neighbours.into_iter().flatten() {
+                if !is_on_wall(neighbour) && visited.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        true
+    }
 }
 
 fn main() {
@@ -290,22 +433,22 @@ fn part2(grid: &Grid) -> u64 {
 }
 
 fn parse_file(path: &str) -> Result<Grid> {
-    let file = File::open(path)?;
-    let points = BufReader::new(file)
+    let input = fs::read_to_string(path)?;
+    parse_str(&input)
+}
+
+fn parse_str(input: &str) -> Result<Grid> {
+    let points = input
         .lines()
-        .map(|res_line| {
-            res_line
-                .map_err(|_| anyhow!("could not parse line"))
-                .and_then(|line| {
-                    let res_coords: Result<Vec<u64>> = line
-                        .split(',')
-                        .map(|comp| {
-                            comp.parse::<u64>()
-                                .map_err(|_| anyhow!("could not parse number"))
-                        })
-                        .collect();
-                    res_coords.map(|coords| Point(coords[0], coords[1]))
+        .map(|line| {
+            let res_coords: Result<Vec<u64>> = line
+                .split(',')
+                .map(|comp| {
+                    comp.parse::<u64>()
+                        .map_err(|_| anyhow!("could not parse number"))
                 })
+                .collect();
+            res_coords.map(|coords| Point(coords[0], coords[1]))
         })
         .collect::<Result<Vec<_>>>()?;
     Ok(Grid::new(points))
@@ -359,4 +502,79 @@ mod tests {
         let walls = grid.get_walls();
         assert!(walls.is_inside(Point(2, 4)));
     }
+
+    #[test]
+    fn test_to_svg() {
+        let grid = parse_file("./files/test.txt").expect("could not run");
+        let walls = grid.get_walls();
+        let svg = walls.to_svg();
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("</svg>"));
+        assert_eq!(
+            svg.matches("<line").count(),
+            walls.x_walls.len() + walls.y_walls.len()
+        );
+        let document = roxmltree::Document::parse(&svg).expect("invalid svg");
+        assert_eq!(
+            document
+                .root_element()
+                .children()
+                .filter(|node| node.has_tag_name("line"))
+                .count(),
+            8
+        );
+
+        let empty = parse_str("").expect("could not parse").get_walls();
+        let svg = empty.to_svg();
+        let document = roxmltree::Document::parse(&svg).expect("invalid svg");
+        assert_eq!(document.root_element().children().count(), 0);
+        let unset = Walls {
+            x_left: u64::MAX,
+            x_right: 0,
+            x_walls: BTreeMap::new(),
+            y_walls: BTreeMap::new(),
+        };
+        assert_eq!(unset.to_svg(), svg);
+    }
+
+    #[test]
+    fn test_is_inside_matches_flood_fill() {
+        let grid = parse_file("./files/test.txt").expect("could not run");
+        let walls = grid.get_walls();
+        let max_y = grid.points.iter().map(|point| point.1).max().unwrap_or(0);
+        for x in walls.x_left..=walls.x_right {
+            for y in 0..=max_y + 1 {
+                let point = Point(x, y);
+                assert_eq!(
+                    walls.is_inside(point),
+                    grid.is_inside_flood_fill(&walls, point),
+                    "{x} {y} disagrees"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_point_ops() {
+        assert_eq!(Point(2, 3) + Point(4, 5), Point(6, 8));
+        assert_eq!(Point(7, 5) - Point(4, 1), Point(3, 4));
+        assert_eq!(Point(1, 5) - Point(4, 1), Point(0, 4));
+        assert_eq!(Point(u64::MAX, 1) + Point(1, 1), Point(u64::MAX, 2));
+        assert_eq!(Point(2, 9).manhattan_distance(&Point(7, 3)), 11);
+        assert_eq!(Point(2, 9).chebyshev_distance(&Point(7, 3)), 6);
+        assert_eq!(Point(7, 3).to_string(), "(7, 3)");
+    }
+
+    #[test]
+    fn test_parse_str() {
+        let grid = parse_str("1,2\n3,4\n").expect("could not parse");
+        assert_eq!(grid, Grid::from_points(vec![Point(1, 2), Point(3, 4)]));
+        assert!(parse_str("1,a\n").is_err());
+
+        let input = fs::read_to_string("./files/test.txt").expect("could not read");
+        assert_eq!(
+            parse_str(&input).expect("could not parse"),
+            parse_file("./files/test.txt").expect("could not parse")
+        );
+    }
 }